@@ -0,0 +1,104 @@
+use crate::report::{Part, Report};
+use serde::Deserialize;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Known-good answers for a puzzle input, read from a TOML file with
+/// optional `part1` and `part2` keys. Answers may be strings or numbers;
+/// multi-line answers are compared ignoring trailing whitespace.
+#[derive(Debug, Default, Deserialize)]
+pub struct Answers {
+    part1: Option<Value>,
+    part2: Option<Value>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = read_to_string(path).map_err(|err| {
+            format!("Failed to read file '{}': {}", path.display(), err)
+        })?;
+        toml::from_str(&contents).map_err(|err| {
+            format!("Failed to parse file '{}': {}", path.display(), err)
+        })
+    }
+
+    /// Loads the answers file next to the given input file. There are no
+    /// expected answers if the file doesn't exist.
+    pub fn for_input(input: &Path) -> Result<Self, String> {
+        let path = Self::path_for_input(input);
+        if path.exists() {
+            Self::load(&path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Loads the answers file given on the command line, if any, or else
+    /// the one next to the input file.
+    pub fn find(path: Option<&str>, input: &Path) -> Result<Self, String> {
+        match path {
+            Some(path) => Self::load(Path::new(path)),
+            None => Self::for_input(input),
+        }
+    }
+
+    pub fn path_for_input(input: &Path) -> PathBuf {
+        input.with_file_name(ANSWERS_FILE)
+    }
+
+    pub fn expected(&self, part: Part) -> Option<String> {
+        let value = match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }?;
+        match value {
+            Value::String(text) => Some(text.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    /// Sets the expected answer of every report.
+    pub fn check(&self, reports: Vec<Report>) -> Vec<Report> {
+        reports
+            .into_iter()
+            .map(|report| {
+                let expected = self.expected(report.part);
+                report.expect(expected)
+            })
+            .collect()
+    }
+}
+
+/// Normalises an answer for comparison by removing trailing whitespace
+/// from each line and trailing empty lines.
+pub fn normalize(answer: &str) -> String {
+    answer
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_answers() {
+        let answers: Answers =
+            toml::from_str("part1 = 279\npart2 = \"\"\"\n #  \n# #\n\"\"\"")
+                .unwrap();
+        assert_eq!(answers.expected(Part::One), Some("279".to_string()));
+        assert_eq!(
+            answers.expected(Part::Two).map(|text| normalize(&text)),
+            Some(" #\n# #".to_string())
+        );
+        let answers: Answers = toml::from_str("part2 = 'abc'").unwrap();
+        assert_eq!(answers.expected(Part::One), None);
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::{Display, Formatter};
+
+/// Location of an error in the puzzle input. Lines and columns are 1-based
+/// and columns count characters, not bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Text that does not follow the puzzle's input format
+    InvalidFormat {
+        position: Position,
+        text: String,
+        reason: String,
+    },
+    /// Well-formed text with a value that cannot be used, such as a number
+    /// too large for its type
+    InvalidValue {
+        position: Position,
+        text: String,
+        reason: String,
+    },
+}
+
+impl Error {
+    /// Creates an `InvalidFormat` error for text found at `column` of the
+    /// first line; use `at_line` to place it on another line of the input.
+    pub fn format<T, R>(column: usize, text: T, reason: R) -> Self
+    where
+        T: Into<String>,
+        R: ToString,
+    {
+        Self::InvalidFormat {
+            position: Position { line: 1, column },
+            text: text.into(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Creates an `InvalidValue` error for text found at `column` of the
+    /// first line; use `at_line` to place it on another line of the input.
+    pub fn value<T, R>(column: usize, text: T, reason: R) -> Self
+    where
+        T: Into<String>,
+        R: ToString,
+    {
+        Self::InvalidValue {
+            position: Position { line: 1, column },
+            text: text.into(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        match self {
+            Self::InvalidFormat { position, .. } => *position,
+            Self::InvalidValue { position, .. } => *position,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            Self::InvalidFormat { text, .. } => text,
+            Self::InvalidValue { text, .. } => text,
+        }
+    }
+
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::InvalidFormat { reason, .. } => reason,
+            Self::InvalidValue { reason, .. } => reason,
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.position_mut().line = line;
+        self
+    }
+
+    /// Moves the error `columns` characters to the right, for errors found
+    /// while parsing a token that does not start at the beginning of a line.
+    pub fn shifted(mut self, columns: usize) -> Self {
+        self.position_mut().column += columns;
+        self
+    }
+
+    fn position_mut(&mut self) -> &mut Position {
+        match self {
+            Self::InvalidFormat { position, .. } => position,
+            Self::InvalidValue { position, .. } => position,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let position = self.position();
+        write!(
+            f,
+            "{} at line {}, column {}: '{}'",
+            self.reason(),
+            position.line,
+            position.column,
+            self.text()
+        )
+    }
+}
+
+impl std::error::Error for Error {}
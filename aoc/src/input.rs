@@ -0,0 +1,253 @@
+use std::env;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+pub const YEAR: u32 = 2016;
+
+/// Source of puzzle inputs that are not in the cache yet.
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, String>;
+}
+
+/// Downloads puzzle inputs from the Advent of Code site, authenticating
+/// with the session token found in the browser cookie of a logged in user.
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+}
+
+impl HttpFetcher {
+    pub const BASE_URL: &'static str = "https://adventofcode.com";
+    pub const SESSION_VAR: &'static str = "AOC_SESSION";
+
+    pub fn new<S: Into<String>>(session: S) -> Self {
+        Self {
+            base_url: Self::BASE_URL.to_string(),
+            session: session.into(),
+        }
+    }
+
+    /// Creates a fetcher with the session token in the `AOC_SESSION`
+    /// environment variable, if it is set.
+    pub fn from_env() -> Option<Self> {
+        env::var(Self::SESSION_VAR).ok().map(Self::new)
+    }
+
+    /// Fetches inputs from another server, such as a local stand-in.
+    pub fn with_base_url<S: Into<String>>(self, base_url: S) -> Self {
+        Self {
+            base_url: base_url.into(),
+            ..self
+        }
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/scarvalhojr/adventofcode2016")
+            .call()
+            .map_err(|err| format!("Failed to fetch '{}': {}", url, err))?
+            .into_string()
+            .map_err(|err| format!("Failed to read '{}': {}", url, err))
+    }
+}
+
+/// Copies puzzle inputs from a local directory with the same layout as the
+/// cache.
+pub struct DirFetcher {
+    dir: PathBuf,
+}
+
+impl DirFetcher {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Fetcher for DirFetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, String> {
+        let path = cache_path(&self.dir, year, day);
+        read_to_string(&path).map_err(|err| {
+            format!("Failed to read file '{}': {}", path.display(), err)
+        })
+    }
+}
+
+/// Puzzle inputs stored as `<dir>/<year>/day<NN>.txt`, fetched on first
+/// use.
+pub struct InputCache {
+    dir: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl InputCache {
+    pub const DIR_VAR: &'static str = "AOC_CACHE_DIR";
+
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            fetcher: None,
+        }
+    }
+
+    /// Creates a cache in the directory given by `AOC_CACHE_DIR`, or else
+    /// `aoc` under the user's cache directory, that fetches missing inputs
+    /// from the Advent of Code site if `AOC_SESSION` is set.
+    pub fn from_env() -> Self {
+        let cache = Self::new(Self::default_dir());
+        match HttpFetcher::from_env() {
+            Some(fetcher) => cache.with_fetcher(fetcher),
+            None => cache,
+        }
+    }
+
+    pub fn default_dir() -> PathBuf {
+        if let Some(dir) = env::var_os(Self::DIR_VAR) {
+            PathBuf::from(dir)
+        } else if let Some(dir) = env::var_os("XDG_CACHE_HOME") {
+            Path::new(&dir).join("aoc")
+        } else if let Some(home) = env::var_os("HOME") {
+            Path::new(&home).join(".cache").join("aoc")
+        } else {
+            PathBuf::from(".aoc-cache")
+        }
+    }
+
+    pub fn with_fetcher<F: Fetcher + 'static>(self, fetcher: F) -> Self {
+        Self {
+            fetcher: Some(Box::new(fetcher)),
+            ..self
+        }
+    }
+
+    pub fn in_dir<P: Into<PathBuf>>(self, dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            ..self
+        }
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        cache_path(&self.dir, year, day)
+    }
+
+    /// Returns the path of the cached input, fetching it first if needed.
+    pub fn resolve(&self, year: u32, day: u32) -> Result<PathBuf, String> {
+        let path = self.path(year, day);
+        if path.exists() {
+            return Ok(path);
+        }
+        let fetcher = self.fetcher.as_ref().ok_or_else(|| {
+            format!(
+                "Input for day {} of {} is not cached in '{}' and {} is not set",
+                day,
+                year,
+                self.dir.display(),
+                HttpFetcher::SESSION_VAR
+            )
+        })?;
+        let contents = fetcher.fetch(year, day)?;
+        path.parent()
+            .map_or(Ok(()), create_dir_all)
+            .and_then(|_| write(&path, contents))
+            .map_err(|err| {
+                format!("Failed to write file '{}': {}", path.display(), err)
+            })?;
+        Ok(path)
+    }
+}
+
+fn cache_path(dir: &Path, year: u32, day: u32) -> PathBuf {
+    dir.join(year.to_string())
+        .join(format!("day{:02}.txt", day))
+}
+
+/// Reads the puzzle input from the given file or, if there is none, from
+/// the input cache. Returns the input and the file it was read from.
+pub fn read_input(
+    filename: Option<&str>,
+    day: u32,
+) -> Result<(String, PathBuf), String> {
+    let path = match filename {
+        Some(filename) => PathBuf::from(filename),
+        None => InputCache::from_env().resolve(YEAR, day)?,
+    };
+    read_to_string(&path)
+        .map(|contents| (contents, path.clone()))
+        .map_err(|err| {
+            format!("Failed to read file '{}': {}", path.display(), err)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::remove_dir_all;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "aoc-input-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetches_missing_inputs_once() {
+        let source = temp_dir("source");
+        let cache_dir = temp_dir("cache");
+        create_dir_all(source.join("2016")).unwrap();
+        write(source.join("2016").join("day03.txt"), "1 2 3\n").unwrap();
+
+        let cache =
+            InputCache::new(&cache_dir).with_fetcher(DirFetcher::new(&source));
+        let path = cache.resolve(2016, 3).unwrap();
+        assert_eq!(path, cache_dir.join("2016").join("day03.txt"));
+        assert_eq!(read_to_string(&path).unwrap(), "1 2 3\n");
+
+        // Once cached, the input no longer needs to be fetched
+        remove_dir_all(&source).unwrap();
+        assert_eq!(cache.resolve(2016, 3).unwrap(), path);
+        assert!(cache.resolve(2016, 4).is_err());
+        assert!(InputCache::new(&cache_dir).resolve(2016, 5).is_err());
+        remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn http_fetcher_sends_session() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<_>>();
+            let body = "R2, L3\n";
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+
+        let fetcher = HttpFetcher::new("secret")
+            .with_base_url(format!("http://{}", address));
+        assert_eq!(fetcher.fetch(2016, 1), Ok("R2, L3\n".to_string()));
+        let request = server.join().unwrap();
+        assert_eq!(request[0], "GET /2016/day/1/input HTTP/1.1");
+        assert!(request.iter().any(|line| line == "Cookie: session=secret"));
+    }
+}
@@ -0,0 +1,97 @@
+pub mod answers;
+mod error;
+pub mod input;
+pub mod report;
+pub mod stats;
+
+pub use error::{Error, Position};
+
+use serde::Serialize;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A solution to one day's puzzle: the puzzle input is parsed once into a
+/// typed representation which is then used to solve both parts. Parts that
+/// may have no answer return `Ok(None)`.
+pub trait Solution {
+    type Input;
+    type Answer1: Display + Serialize;
+    type Answer2: Display + Serialize;
+
+    const DAY: u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+
+    fn part1(input: &Self::Input) -> Result<Option<Self::Answer1>, Error>;
+
+    fn part2(input: &Self::Input) -> Result<Option<Self::Answer2>, Error>;
+}
+
+/// Parses every line of the input, reporting errors at their line number.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, Error>
+where
+    T: FromStr<Err = Error>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.parse().map_err(|err: Error| err.at_line(index + 1))
+        })
+        .collect()
+}
+
+/// Returns the 1-based column of the character at `byte_index` of `line`.
+pub fn column_at(line: &str, byte_index: usize) -> usize {
+    line[..byte_index].chars().count() + 1
+}
+
+/// Splits a line into whitespace-separated words, each with its 1-based
+/// column.
+pub fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace().map(move |word| {
+        (
+            column_at(line, word.as_ptr() as usize - line.as_ptr() as usize),
+            word,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Digit(u32);
+
+    impl FromStr for Digit {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.parse().map(Digit).map_err(|err| Error::value(1, s, err))
+        }
+    }
+
+    #[test]
+    fn errors_report_line() {
+        let digits = parse_lines::<Digit>("1\n2\n3").unwrap();
+        assert_eq!(digits.iter().map(|digit| digit.0).sum::<u32>(), 6);
+
+        let err = parse_lines::<Digit>("1\n2\nx\n4").err().unwrap();
+        assert_eq!(err.position(), Position { line: 3, column: 1 });
+        assert_eq!(err.text(), "x");
+        assert_eq!(
+            err.to_string(),
+            "invalid digit found in string at line 3, column 1: 'x'"
+        );
+    }
+
+    #[test]
+    fn columns_count_chars() {
+        assert_eq!(column_at("ab€cd", 0), 1);
+        assert_eq!(column_at("ab€cd", 5), 4);
+        assert_eq!(
+            words(" 12  €3 4").collect::<Vec<_>>(),
+            vec![(2, "12"), (6, "€3"), (9, "4")]
+        );
+    }
+}
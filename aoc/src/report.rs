@@ -0,0 +1,391 @@
+use crate::answers::normalize;
+use crate::stats::{collect_counters, format_bytes, peak_allocation, Stats};
+use crate::{Error, Solution};
+use clap::Arg;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::process::exit;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u32 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Invalid format: {}", s)),
+        }
+    }
+}
+
+/// The `--format` argument shared by all binaries.
+pub fn format_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("FORMAT")
+        .long("format")
+        .help("Sets the output format")
+        .possible_values(&["text", "json"])
+        .default_value("text")
+}
+
+/// The `--answers` argument shared by all binaries.
+pub fn answers_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("ANSWERS")
+        .long("answers")
+        .takes_value(true)
+        .help("File with the expected answers [default: answers.toml next to the input]")
+}
+
+/// The `--stats` argument shared by all binaries.
+pub fn stats_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("STATS")
+        .long("stats")
+        .help("Reports parse time, time and peak allocation of each part")
+}
+
+/// An answer in both of its printed forms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    pub text: String,
+    pub value: Value,
+}
+
+impl Answer {
+    fn new<T: Display + Serialize>(answer: &T) -> Self {
+        Self {
+            text: answer.to_string(),
+            value: serde_json::to_value(answer)
+                .unwrap_or_else(|_| Value::String(answer.to_string())),
+        }
+    }
+}
+
+/// The outcome of solving one part of a puzzle.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub day: u32,
+    pub part: Part,
+    pub answer: Result<Option<Answer>, Error>,
+    pub elapsed: Duration,
+    pub expected: Option<String>,
+    pub stats: Stats,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    day: u32,
+    part: u32,
+    answer: Option<&'a Value>,
+    found: bool,
+    elapsed_secs: f64,
+    error: Option<String>,
+    expected: Option<&'a str>,
+    correct: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<JsonStats<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonStats<'a> {
+    parse_secs: f64,
+    peak_bytes: Option<usize>,
+    counters: BTreeMap<&'a str, usize>,
+}
+
+impl Report {
+    /// Runs `solve`, recording how long it takes, its peak allocation and
+    /// any statistics it records.
+    pub fn time<T, F>(day: u32, part: Part, solve: F) -> Self
+    where
+        T: Display + Serialize,
+        F: FnOnce() -> Result<Option<T>, Error>,
+    {
+        let start = Instant::now();
+        let ((result, peak_bytes), counters) =
+            collect_counters(|| peak_allocation(solve));
+        let elapsed = start.elapsed();
+        Self {
+            day,
+            part,
+            answer: result.map(|answer| answer.as_ref().map(Answer::new)),
+            elapsed,
+            expected: None,
+            stats: Stats {
+                peak_bytes,
+                counters,
+                ..Stats::default()
+            },
+        }
+    }
+
+    /// A report for a part that could not be solved because of `err`.
+    pub fn failed(day: u32, part: Part, err: Error) -> Self {
+        Self {
+            day,
+            part,
+            answer: Err(err),
+            elapsed: Duration::default(),
+            expected: None,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Sets how long it took to parse the input this part was solved from.
+    pub fn parsed_in(mut self, parse_time: Duration) -> Self {
+        self.stats.parse_time = parse_time;
+        self
+    }
+
+    /// Sets the answer this part is expected to have.
+    pub fn expect(self, expected: Option<String>) -> Self {
+        Self { expected, ..self }
+    }
+
+    /// Whether the answer matches the expected one, if there is one.
+    pub fn is_correct(&self) -> Option<bool> {
+        let expected = normalize(self.expected.as_ref()?);
+        Some(match &self.answer {
+            Ok(Some(answer)) => normalize(&answer.text) == expected,
+            _ => false,
+        })
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.answer.is_err()
+    }
+
+    /// Prints the answer and, if `stats` is set, its performance statistics.
+    pub fn print(&self, format: Format, stats: bool) {
+        match format {
+            Format::Text => match &self.answer {
+                Ok(Some(answer)) if answer.text.contains('\n') => {
+                    println!("Part {}:\n{}", self.part.number(), answer.text)
+                }
+                Ok(Some(answer)) => {
+                    println!("Part {}: {}", self.part.number(), answer.text)
+                }
+                Ok(None) => println!("Part {}: not found", self.part.number()),
+                Err(err) => println!("Part {}: {}", self.part.number(), err),
+            },
+            Format::Json => println!("{}", self.to_json(stats)),
+        }
+        if format == Format::Text && stats {
+            self.print_stats();
+        }
+        if format == Format::Text && self.is_correct() == Some(false) {
+            self.print_mismatch();
+        }
+    }
+
+    fn print_stats(&self) {
+        print!(
+            "  parse: {:?}, part: {:?}",
+            self.stats.parse_time, self.elapsed
+        );
+        match self.stats.peak_bytes {
+            Some(bytes) => {
+                println!(", peak allocation: {}", format_bytes(bytes))
+            }
+            None => println!(),
+        }
+        for (name, value) in &self.stats.counters {
+            println!("  {}: {}", name, value);
+        }
+    }
+
+    fn print_mismatch(&self) {
+        println!(
+            "Part {} does not match the expected answer:",
+            self.part.number()
+        );
+        let actual = match &self.answer {
+            Ok(Some(answer)) => normalize(&answer.text),
+            _ => String::new(),
+        };
+        for line in normalize(self.expected.as_ref().unwrap()).lines() {
+            println!("- {}", line);
+        }
+        for line in actual.lines() {
+            println!("+ {}", line);
+        }
+    }
+
+    pub fn to_json(&self, stats: bool) -> String {
+        let answer = self.answer.as_ref().ok().and_then(Option::as_ref);
+        serde_json::to_string(&JsonReport {
+            day: self.day,
+            part: self.part.number(),
+            answer: answer.map(|answer| &answer.value),
+            found: answer.is_some(),
+            elapsed_secs: self.elapsed.as_secs_f64(),
+            error: self.answer.as_ref().err().map(Error::to_string),
+            expected: self.expected.as_deref(),
+            correct: self.is_correct(),
+            stats: if stats {
+                Some(JsonStats {
+                    parse_secs: self.stats.parse_time.as_secs_f64(),
+                    peak_bytes: self.stats.peak_bytes,
+                    counters: self.stats.counters.iter().copied().collect(),
+                })
+            } else {
+                None
+            },
+        })
+        .unwrap()
+    }
+}
+
+/// The JSON record of an error that keeps a day from running.
+fn error_json(day: u32, err: &dyn Display) -> String {
+    serde_json::json!({ "day": day, "error": err.to_string() }).to_string()
+}
+
+/// Prints an error that keeps a day from running: a line of text, or a
+/// record with an `error` field so that JSON output stays one record per
+/// line.
+pub fn print_error(day: u32, format: Format, err: &dyn Display) {
+    match format {
+        Format::Text => println!("{}", err),
+        Format::Json => println!("{}", error_json(day, err)),
+    }
+}
+
+/// A handler for an error that keeps a day from running, which prints it
+/// and exits with `code`, as in `.unwrap_or_else(exit_with_error(..))`.
+pub fn exit_with_error<E: Display, T>(
+    day: u32,
+    format: Format,
+    code: i32,
+) -> impl FnOnce(E) -> T {
+    move |err| {
+        print_error(day, format, &err);
+        exit(code)
+    }
+}
+
+/// Returns the exit code for a run: 3 if any part failed, 4 if any answer
+/// didn't match the expected answer and 0 otherwise.
+pub fn exit_code(reports: &[Report]) -> i32 {
+    if reports.iter().any(Report::is_error) {
+        3
+    } else if reports
+        .iter()
+        .any(|report| report.is_correct() == Some(false))
+    {
+        4
+    } else {
+        0
+    }
+}
+
+/// Parses the puzzle input and solves the requested parts. If the input
+/// cannot be parsed, every part is reported with the parsing error.
+pub fn run<S: Solution>(contents: &str, parts: &[Part]) -> Vec<Report> {
+    let start = Instant::now();
+    let input = S::parse(contents);
+    let parse_time = start.elapsed();
+    parts
+        .iter()
+        .map(|&part| match (&input, part) {
+            (Ok(input), Part::One) => {
+                Report::time(S::DAY, part, || S::part1(input))
+            }
+            (Ok(input), Part::Two) => {
+                Report::time(S::DAY, part, || S::part2(input))
+            }
+            (Err(err), _) => Report::failed(S::DAY, part, err.clone()),
+        })
+        .map(|report| report.parsed_in(parse_time))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_records() {
+        let report = Report::time(3, Part::Two, || Ok(Some(42)));
+        assert!(report
+            .to_json(false)
+            .starts_with(r#"{"day":3,"part":2,"answer":42,"found":true,"#));
+        assert!(report
+            .to_json(false)
+            .ends_with(r#","error":null,"expected":null,"correct":null}"#));
+
+        let report = Report::time(1, Part::One, || Ok(None::<usize>));
+        assert!(report
+            .to_json(false)
+            .contains(r#""answer":null,"found":false"#));
+
+        let report = Report::time(1, Part::One, || {
+            Err::<Option<usize>, _>(Error::format(4, "X", "Invalid turn"))
+        });
+        assert!(report
+            .to_json(false)
+            .contains(r#""error":"Invalid turn at line 1, column 4: 'X'","#));
+    }
+
+    #[test]
+    fn error_records() {
+        assert_eq!(
+            error_json(2, &"Input for day 2 is not cached"),
+            r#"{"day":2,"error":"Input for day 2 is not cached"}"#
+        );
+    }
+
+    #[test]
+    fn expected_answers() {
+        let report = Report::time(1, Part::One, || Ok(Some(42)));
+        assert_eq!(report.is_correct(), None);
+        let report = report.expect(Some("42".to_string()));
+        assert_eq!(report.is_correct(), Some(true));
+        assert_eq!(exit_code(&[report]), 0);
+
+        let report = Report::time(1, Part::One, || Ok(Some(42)))
+            .expect(Some("43".to_string()));
+        assert_eq!(report.is_correct(), Some(false));
+        assert!(report
+            .to_json(false)
+            .ends_with(r#""expected":"43","correct":false}"#));
+        assert_eq!(exit_code(&[report]), 4);
+    }
+
+    #[test]
+    fn stats_records() {
+        let report = Report::time(11, Part::One, || {
+            crate::stats::record("states expanded", 7);
+            Ok(Some(42))
+        })
+        .parsed_in(Duration::from_millis(1500));
+        assert_eq!(report.stats.counters, vec![("states expanded", 7)]);
+        assert!(!report.to_json(false).contains("stats"));
+        assert!(report.to_json(true).contains(
+            r#""stats":{"parse_secs":1.5,"peak_bytes":null,"counters":{"states expanded":7}}"#
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Whether statistics are being collected, so that they are only kept
+    /// while a part is timed and not by library or benchmark callers
+    static COLLECTING: Cell<bool> = const { Cell::new(false) };
+    static COUNTERS: RefCell<Vec<(&'static str, usize)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// A global allocator that keeps track of the number of bytes allocated so
+/// that peak allocations can be reported. Binaries opt in with:
+///
+/// ```
+/// use aoc::stats::TrackingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+/// ```
+pub struct TrackingAllocator;
+
+impl TrackingAllocator {
+    fn allocated(size: usize) {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            INSTALLED.store(true, Ordering::Relaxed);
+        }
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn freed(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::freed(layout.size());
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::freed(layout.size());
+            Self::allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Runs `f`, returning the peak number of bytes allocated while it ran on
+/// top of what was already allocated. There is no peak unless the
+/// `TrackingAllocator` is the global allocator.
+pub fn peak_allocation<T, F: FnOnce() -> T>(f: F) -> (T, Option<usize>) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let result = f();
+    let peak = PEAK.load(Ordering::Relaxed).saturating_sub(start);
+    (
+        result,
+        Some(peak).filter(|_| INSTALLED.load(Ordering::Relaxed)),
+    )
+}
+
+/// Records a statistic of the part being solved, such as the number of
+/// states explored by a search, to be reported along with its answer.
+/// Statistics are only kept while `collect_counters` runs.
+pub fn record(name: &'static str, value: usize) {
+    if COLLECTING.with(Cell::get) {
+        COUNTERS.with(|counters| counters.borrow_mut().push((name, value)));
+    }
+}
+
+/// Runs `f`, returning the statistics it recorded on this thread.
+pub fn collect_counters<T, F: FnOnce() -> T>(
+    f: F,
+) -> (T, Vec<(&'static str, usize)>) {
+    let outer = COLLECTING.with(|collecting| collecting.replace(true));
+    let recorded = COUNTERS.with(|counters| counters.replace(Vec::new()));
+    let result = f();
+    let counters = COUNTERS.with(|counters| counters.replace(recorded));
+    COLLECTING.with(|collecting| collecting.set(outer));
+    (result, counters)
+}
+
+/// Performance statistics of solving one part of a puzzle.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub parse_time: Duration,
+    pub peak_bytes: Option<usize>,
+    pub counters: Vec<(&'static str, usize)>,
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_are_only_collected_when_asked() {
+        record("states expanded", 1);
+        let ((), counters) = collect_counters(|| {
+            record("states expanded", 12);
+            record("max heap size", 3);
+        });
+        assert_eq!(
+            counters,
+            vec![("states expanded", 12), ("max heap size", 3)]
+        );
+        record("states expanded", 1);
+        assert!(COUNTERS.with(|counters| counters.borrow().is_empty()));
+        assert!(collect_counters(|| ()).1.is_empty());
+    }
+
+    #[test]
+    fn bytes_use_binary_units() {
+        assert_eq!(format_bytes(1000), "1000 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
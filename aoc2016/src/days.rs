@@ -0,0 +1,83 @@
+use aoc::report::{run, Part, Report};
+use aoc::Solution;
+use day01::Day01;
+use day02::Day02;
+use day03::Day03;
+use day04::Day04;
+use day05::Day05;
+use day06::Day06;
+use day07::Day07;
+use day08::Day08;
+use day09::Day09;
+use day11::Day11;
+
+type Solver = fn(&str, &[Part]) -> Vec<Report>;
+
+pub struct Day {
+    pub number: u32,
+    pub input: &'static str,
+    solver: Solver,
+}
+
+impl Day {
+    pub fn solve(&self, contents: &str, parts: &[Part]) -> Vec<Report> {
+        (self.solver)(contents, parts)
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: Day01::DAY,
+        input: "day01/input.txt",
+        solver: run::<Day01>,
+    },
+    Day {
+        number: Day02::DAY,
+        input: "day02/input.txt",
+        solver: run::<Day02>,
+    },
+    Day {
+        number: Day03::DAY,
+        input: "day03/input.txt",
+        solver: run::<Day03>,
+    },
+    Day {
+        number: Day04::DAY,
+        input: "day04/input.txt",
+        solver: run::<Day04>,
+    },
+    Day {
+        number: Day05::DAY,
+        input: "day05/input.txt",
+        solver: run::<Day05>,
+    },
+    Day {
+        number: Day06::DAY,
+        input: "day06/input.txt",
+        solver: run::<Day06>,
+    },
+    Day {
+        number: Day07::DAY,
+        input: "day07/input.txt",
+        solver: run::<Day07>,
+    },
+    Day {
+        number: Day08::DAY,
+        input: "day08/input.txt",
+        solver: run::<Day08>,
+    },
+    Day {
+        number: Day09::DAY,
+        input: "day09/input.txt",
+        solver: run::<Day09>,
+    },
+    Day {
+        number: Day11::DAY,
+        input: "day11/input",
+        solver: run::<Day11>,
+    },
+];
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -0,0 +1,194 @@
+mod days;
+
+use aoc::answers::Answers;
+use aoc::input::{InputCache, YEAR};
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, print_error,
+    stats_arg, Format, Part, Report,
+};
+use aoc::stats::TrackingAllocator;
+use clap::{
+    crate_description, value_t_or_exit, App, AppSettings, Arg, SubCommand,
+};
+use days::{find_day, Day, DAYS};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn main() {
+    let args = App::new(crate_description!())
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("CACHE_DIR")
+                .long("cache-dir")
+                .takes_value(true)
+                .global(true)
+                .help("Directory of the input cache [default: $AOC_CACHE_DIR or ~/.cache/aoc]"),
+        )
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Runs the solution of a single day")
+                .setting(AppSettings::AllowMissingPositional)
+                .arg(
+                    Arg::with_name("DAY")
+                        .help("The day to run")
+                        .required_unless("CACHED_DAY")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("PART")
+                        .help("The part to run (1 or 2); runs both if omitted")
+                        .possible_values(&["1", "2"])
+                        .index(2),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .help("Sets the input file to use")
+                        .required_unless("CACHED_DAY")
+                        .index(3),
+                )
+                .arg(
+                    Arg::with_name("CACHED_DAY")
+                        .long("day")
+                        .takes_value(true)
+                        .value_name("N")
+                        .conflicts_with_all(&["DAY", "INPUT"])
+                        .help("Runs day N with its cached input"),
+                )
+                .arg(
+                    Arg::with_name("CACHED_PART")
+                        .long("part")
+                        .takes_value(true)
+                        .value_name("P")
+                        .possible_values(&["1", "2"])
+                        .requires("CACHED_DAY")
+                        .help("The part to run with --day; runs both if omitted"),
+                )
+                .arg(format_arg())
+                .arg(answers_arg())
+                .arg(stats_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("all")
+                .about("Runs the solutions of all days in sequence")
+                .arg(
+                    Arg::with_name("DIR")
+                        .help("Workspace directory with the input files; missing inputs are read from the cache")
+                        .default_value(".")
+                        .index(1),
+                )
+                .arg(format_arg())
+                .arg(stats_arg()),
+        )
+        .get_matches();
+
+    let (name, sub_args) = args.subcommand();
+    let sub_args = sub_args.unwrap();
+    let format = value_t_or_exit!(sub_args.value_of("FORMAT"), Format);
+    let stats = sub_args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+
+    let cache = match sub_args.value_of("CACHE_DIR") {
+        Some(dir) => InputCache::from_env().in_dir(dir),
+        None => InputCache::from_env(),
+    };
+
+    let mut reports = Vec::new();
+    let mut missing = false;
+    match name {
+        "run" => {
+            let (number, part, input) = if sub_args.is_present("CACHED_DAY") {
+                let number =
+                    value_t_or_exit!(sub_args.value_of("CACHED_DAY"), u32);
+                (number, sub_args.value_of("CACHED_PART"), None)
+            } else {
+                let number = value_t_or_exit!(sub_args.value_of("DAY"), u32);
+                (
+                    number,
+                    sub_args.value_of("PART"),
+                    sub_args.value_of("INPUT"),
+                )
+            };
+            let day = find_day(number)
+                .ok_or_else(|| format!("No solution for day {}", number))
+                .unwrap_or_else(exit_with_error(number, format, 1));
+            let parts = match part {
+                Some("1") => vec![Part::One],
+                Some("2") => vec![Part::Two],
+                _ => vec![Part::One, Part::Two],
+            };
+            let filename = match input {
+                Some(input) => PathBuf::from(input),
+                None => cache
+                    .resolve(YEAR, number)
+                    .unwrap_or_else(exit_with_error(number, format, 2)),
+            };
+            let answers =
+                Answers::find(sub_args.value_of("ANSWERS"), &filename)
+                    .unwrap_or_else(exit_with_error(number, format, 2));
+            reports = run(day, &parts, &filename, &answers, format, stats);
+        }
+        "all" => {
+            let dir = Path::new(sub_args.value_of("DIR").unwrap());
+            for day in DAYS {
+                let mut filename = dir.join(day.input);
+                if !filename.exists() {
+                    // Not every input is checked in; report days whose
+                    // input cannot be found anywhere and go on
+                    filename = match cache.resolve(YEAR, day.number) {
+                        Ok(filename) => filename,
+                        Err(err) => {
+                            if format == Format::Text {
+                                println!("Day {:02}", day.number);
+                            }
+                            print_error(day.number, format, &err);
+                            missing = true;
+                            continue;
+                        }
+                    };
+                }
+                let answers = Answers::for_input(&filename)
+                    .unwrap_or_else(exit_with_error(day.number, format, 2));
+                let parts = [Part::One, Part::Two];
+                reports.extend(run(
+                    day, &parts, &filename, &answers, format, stats,
+                ));
+            }
+        }
+        _ => unreachable!(),
+    }
+    // A day that could not run fails the run like a missing input does for a
+    // single day
+    match exit_code(&reports) {
+        0 if missing => exit(2),
+        code => exit(code),
+    }
+}
+
+fn run(
+    day: &Day,
+    parts: &[Part],
+    filename: &Path,
+    answers: &Answers,
+    format: Format,
+    stats: bool,
+) -> Vec<Report> {
+    if format == Format::Text {
+        println!("Day {:02}", day.number);
+    }
+    let contents = read_to_string(filename)
+        .map_err(|err| {
+            format!("Failed to read file '{}': {}", filename.display(), err)
+        })
+        .unwrap_or_else(exit_with_error(day.number, format, 2));
+    let reports = answers.check(day.solve(&contents, parts));
+    for report in &reports {
+        report.print(format, stats);
+    }
+    reports
+}
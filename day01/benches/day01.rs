@@ -0,0 +1,26 @@
+use aoc::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day01::stream::walk_from;
+use day01::{part1, Day01};
+
+const INPUT: &str = include_str!("../input.txt");
+
+fn parts(c: &mut Criterion) {
+    let input = Day01::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day01");
+    group
+        .bench_function("parse", |b| b.iter(|| Day01::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day01::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day01::part2(black_box(&input)))
+    });
+    group.bench_function("stream part1", |b| {
+        b.iter(|| walk_from(black_box(INPUT.as_bytes()), |m| part1(m)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parts);
+criterion_main!(benches);
@@ -1,70 +1,131 @@
-use std::collections::HashSet;
+pub mod render;
+pub mod stream;
+pub mod sweep;
+pub mod topology;
+
+use aoc::{Error, Solution};
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::str::FromStr;
+use stream::{Movements, ParseError};
+use topology::Square;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Turn {
     Left,
     Right,
+    /// A U-turn, written `B` for back
+    Back,
+    /// No turn, written `F` for forward
+    Forward,
+    /// Pitching up, on grids with a third dimension
+    Up,
+    /// Pitching down, on grids with a third dimension
+    Down,
+}
+
+impl Turn {
+    fn letter(&self) -> char {
+        match self {
+            Self::Left => 'L',
+            Self::Right => 'R',
+            Self::Back => 'B',
+            Self::Forward => 'F',
+            Self::Up => 'U',
+            Self::Down => 'D',
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Movement {
     turn: Turn,
-    steps: i32,
+    steps: u32,
+    /// Where the movement is in the input, for errors found while walking
+    location: aoc::Position,
 }
 
 impl Movement {
-    fn new(turn: Turn, steps: i32) -> Self {
-        Self { turn, steps }
+    fn new(turn: Turn, steps: u32) -> Self {
+        Self {
+            turn,
+            steps,
+            location: aoc::Position { line: 1, column: 1 },
+        }
+    }
+
+    /// The error for a movement that makes a route too long to record.
+    fn too_long(&self) -> Error {
+        Error::value(
+            self.location.column,
+            format!("{}{}", self.turn.letter(), self.steps),
+            format!("Route longer than {} blocks", MAX_ROUTE_BLOCKS),
+        )
+        .at_line(self.location.line)
+    }
+
+    /// The error for a turn that the grid being walked doesn't have.
+    fn unsupported_turn(&self, grid: &str) -> Error {
+        Error::value(
+            self.location.column,
+            self.turn.letter().to_string(),
+            format!("Invalid turn on a {} grid", grid),
+        )
+        .at_line(self.location.line)
     }
 }
 
-#[derive(Debug)]
-enum Direction {
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub enum Direction {
+    #[default]
     North,
     East,
     South,
     West,
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Self::North
-    }
-}
-
 impl Direction {
-    fn turn(&self, turn: &Turn) -> Self {
-        match (self, turn) {
-            (Self::North, Turn::Right) => Self::East,
-            (Self::East, Turn::Right) => Self::South,
-            (Self::South, Turn::Right) => Self::West,
-            (Self::West, Turn::Right) => Self::North,
-            (Self::North, Turn::Left) => Self::West,
-            (Self::West, Turn::Left) => Self::South,
-            (Self::South, Turn::Left) => Self::East,
-            (Self::East, Turn::Left) => Self::North,
-        }
+    const CLOCKWISE: [Self; 4] =
+        [Self::North, Self::East, Self::South, Self::West];
+
+    /// The direction after turning, or `None` for turns that leave the
+    /// ground.
+    fn turn(&self, turn: &Turn) -> Option<Self> {
+        let quarters = match turn {
+            Turn::Forward => 0,
+            Turn::Right => 1,
+            Turn::Back => 2,
+            Turn::Left => 3,
+            Turn::Up | Turn::Down => return None,
+        };
+        let index = Self::CLOCKWISE.iter().position(|dir| dir == self)?;
+        Some(Self::CLOCKWISE[(index + quarters) % 4])
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
-struct Coordinates {
-    pos_x: i32,
-    pos_y: i32,
+/// A point of the city grid; `pos_y` grows towards the south.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+pub struct Coordinates {
+    pub pos_x: i64,
+    pub pos_y: i64,
 }
 
 impl Coordinates {
-    fn origin_distance(&self) -> i32 {
-        self.pos_x.abs() + self.pos_y.abs()
+    pub fn origin_distance(&self) -> i64 {
+        self.distance(&Self::default())
+    }
+
+    pub fn distance(&self, other: &Self) -> i64 {
+        (self.pos_x - other.pos_x).abs() + (self.pos_y - other.pos_y).abs()
     }
 
     fn step(&self, direction: &Direction) -> Self {
         self.jump(direction, 1)
     }
 
-    fn jump(&self, direction: &Direction, steps: i32) -> Self {
+    fn jump(&self, direction: &Direction, steps: i64) -> Self {
         let (pos_x, pos_y) = match direction {
             Direction::North => (self.pos_x, self.pos_y - steps),
             Direction::East => (self.pos_x + steps, self.pos_y),
@@ -86,80 +147,344 @@ impl Position {
         self.coordinates
     }
 
-    fn origin_distance(&self) -> i32 {
-        self.coordinates.origin_distance()
+    fn heading(&self, movement: &Movement) -> Result<Direction, Error> {
+        self.direction
+            .turn(&movement.turn)
+            .ok_or_else(|| movement.unsupported_turn("square"))
     }
 
-    fn jump(&self, movement: &Movement) -> Self {
-        let direction = self.direction.turn(&movement.turn);
-        let coordinates = self.coordinates.jump(&direction, movement.steps);
-        Self {
+    fn jump(&self, movement: &Movement) -> Result<Self, Error> {
+        let direction = self.heading(movement)?;
+        let coordinates =
+            self.coordinates.jump(&direction, i64::from(movement.steps));
+        Ok(Self {
             direction,
             coordinates,
-        }
+        })
     }
 
-    fn walk(&self, movement: &Movement) -> impl Iterator<Item = Coordinates> {
-        let direction = self.direction.turn(&movement.turn);
+    fn walk(
+        &self,
+        movement: &Movement,
+    ) -> Result<impl Iterator<Item = Coordinates>, Error> {
+        let direction = self.heading(movement)?;
         let start = self.coordinates();
-        (0..movement.steps).scan(start, move |coord, _| {
+        Ok((0..movement.steps).scan(start, move |coord, _| {
             *coord = coord.step(&direction);
             Some(*coord)
-        })
+        }))
     }
 }
 
-pub fn part1(movements: &[Movement]) -> i32 {
-    let mut position = Position::default();
-    for movement in movements {
-        position = position.jump(movement);
+/// A point reached while walking: its coordinates, the index of the
+/// movement that reached it and the heading while doing so.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Step {
+    pub movement: usize,
+    pub heading: Direction,
+    pub coordinates: Coordinates,
+}
+
+/// The smallest rectangle containing a set of coordinates.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct BoundingBox {
+    pub min: Coordinates,
+    pub max: Coordinates,
+}
+
+impl BoundingBox {
+    fn new(coordinates: Coordinates) -> Self {
+        Self {
+            min: coordinates,
+            max: coordinates,
+        }
+    }
+
+    fn extend(&self, coordinates: Coordinates) -> Self {
+        Self {
+            min: Coordinates {
+                pos_x: self.min.pos_x.min(coordinates.pos_x),
+                pos_y: self.min.pos_y.min(coordinates.pos_y),
+            },
+            max: Coordinates {
+                pos_x: self.max.pos_x.max(coordinates.pos_x),
+                pos_y: self.max.pos_y.max(coordinates.pos_y),
+            },
+        }
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.pos_x - self.min.pos_x + 1
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.pos_y - self.min.pos_y + 1
     }
-    position.origin_distance()
 }
 
-pub fn part2(movements: &[Movement]) -> Option<i32> {
-    let mut position = Position::default();
-    let mut visited = HashSet::new();
-    visited.insert(position.coordinates());
+/// A point of a walk reached again. Steps count the blocks walked, so the
+/// start point is step 0.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Revisit {
+    pub coordinates: Coordinates,
+    pub first_step: usize,
+    pub step: usize,
+    /// How many times the point has been visited, including this time
+    pub visits: usize,
+}
 
-    for movement in movements {
-        for coordinates in position.walk(movement) {
-            if visited.contains(&coordinates) {
-                return Some(coordinates.origin_distance());
+/// Most blocks a route is recorded with, so that a long walk is reported
+/// as an error instead of running out of memory.
+pub const MAX_ROUTE_BLOCKS: u64 = 1_000_000;
+
+/// The complete trajectory of a walk, one step per block walked.
+#[derive(Clone, Debug, Serialize)]
+pub struct Route {
+    start: Coordinates,
+    steps: Vec<Step>,
+}
+
+impl Route {
+    pub fn record(movements: &[Movement]) -> Result<Self, Error> {
+        let mut position = Position::default();
+        let start = position.coordinates();
+        let mut steps = Vec::new();
+        let mut blocks = 0;
+        for (index, movement) in movements.iter().enumerate() {
+            blocks += u64::from(movement.steps);
+            if blocks > MAX_ROUTE_BLOCKS {
+                return Err(movement.too_long());
             }
-            visited.insert(coordinates);
+            let heading = position.heading(movement)?;
+            steps.extend(position.walk(movement)?.map(|coordinates| Step {
+                movement: index,
+                heading,
+                coordinates,
+            }));
+            position = position.jump(movement)?;
         }
-        position = position.jump(movement);
+        Ok(Self { start, steps })
+    }
+
+    pub fn start(&self) -> Coordinates {
+        self.start
+    }
+
+    pub fn end(&self) -> Coordinates {
+        self.steps
+            .last()
+            .map_or(self.start, |step| step.coordinates)
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Every point of the route in order, starting with the start point.
+    pub fn points(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        std::iter::once(self.start)
+            .chain(self.steps.iter().map(|step| step.coordinates))
+    }
+
+    /// Every time the walk reaches a point it has been at before, in order.
+    pub fn revisits(&self) -> impl Iterator<Item = Revisit> + '_ {
+        let mut visits = HashMap::new();
+        self.points()
+            .enumerate()
+            .filter_map(move |(step, coordinates)| {
+                let (first_step, count) =
+                    visits.entry(coordinates).or_insert((step, 0));
+                *count += 1;
+                if *count > 1 {
+                    Some(Revisit {
+                        coordinates,
+                        first_step: *first_step,
+                        step,
+                        visits: *count,
+                    })
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Up to `limit` points visited more than once with their number of
+    /// visits, most visited first and then in order of first visit.
+    pub fn most_visited(&self, limit: usize) -> Vec<(Coordinates, usize)> {
+        let mut visits = HashMap::new();
+        for revisit in self.revisits() {
+            visits.insert(
+                revisit.coordinates,
+                (revisit.visits, revisit.first_step),
+            );
+        }
+        let mut visits = visits.into_iter().collect::<Vec<_>>();
+        visits.sort_unstable_by(
+            |(_, (count_a, step_a)), (_, (count_b, step_b))| {
+                count_b.cmp(count_a).then(step_a.cmp(step_b))
+            },
+        );
+        visits
+            .into_iter()
+            .take(limit)
+            .map(|(coordinates, (count, _))| (coordinates, count))
+            .collect()
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.points()
+            .fold(BoundingBox::new(self.start), |bbox, coordinates| {
+                bbox.extend(coordinates)
+            })
+    }
+}
+
+pub fn part1<I>(movements: I) -> Result<i64, Error>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Movement>,
+{
+    topology::distance::<Square, _>(movements)
+}
+
+/// Walks block by block until reaching a block visited before. Time and
+/// memory grow with the number of steps; see `sweep::part2` for long walks.
+pub fn part2<I>(movements: I) -> Result<Option<i64>, Error>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Movement>,
+{
+    topology::first_revisit::<Square, _>(movements)
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<Movement>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    const DAY: u32 = 1;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Movements::new(input.as_bytes())
+            .collect::<Result<_, _>>()
+            .map_err(|err| match err {
+                ParseError::Token { error, .. } => error,
+                // Reading from memory never fails
+                ParseError::Io(err) => unreachable!("{}", err),
+            })
+    }
+
+    fn part1(movements: &Self::Input) -> Result<Option<i64>, Error> {
+        part1(movements).map(Some)
+    }
+
+    fn part2(movements: &Self::Input) -> Result<Option<i64>, Error> {
+        sweep::part2(movements)
     }
-    None
 }
 
 impl TryFrom<char> for Turn {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(ch: char) -> Result<Self, Self::Error> {
         match ch.to_ascii_uppercase() {
             'L' => Ok(Self::Left),
             'R' => Ok(Self::Right),
-            _ => Err(format!("Invalid turn: {}", ch)),
+            'B' => Ok(Self::Back),
+            'F' => Ok(Self::Forward),
+            'U' => Ok(Self::Up),
+            'D' => Ok(Self::Down),
+            _ => Err(Error::format(1, ch, "Invalid turn")),
         }
     }
 }
 
 impl FromStr for Movement {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let ch = s
             .chars()
             .next()
-            .ok_or_else(|| "Invalid movement: empty string".to_string())?;
+            .ok_or_else(|| Error::format(1, s, "Missing movement"))?;
         let turn = Turn::try_from(ch)?;
-        let steps = s
-            .get(1..)
-            .unwrap()
-            .parse::<i32>()
-            .map_err(|err| format!("Invalid number of steps: {}", err))?;
+        let steps_str = &s[ch.len_utf8()..];
+        let steps = steps_str.parse::<u32>().map_err(|err| {
+            let negative = steps_str.strip_prefix('-').is_some_and(|digits| {
+                !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+            });
+            let reason = if negative {
+                "Negative number of steps".to_string()
+            } else {
+                format!("Invalid number of steps: {}", err)
+            };
+            Error::value(2, steps_str, reason)
+        })?;
         Ok(Self::new(turn, steps))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors() {
+        let err = Day01::parse("R2, L3,,\nR5, X2").err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 1, column: 8 });
+        assert_eq!(err.reason(), "Missing movement");
+
+        let err = Day01::parse("R2, L3\nR5,  L2x").err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 2, column: 7 });
+        assert_eq!(err.text(), "2x");
+    }
+
+    #[test]
+    fn route() {
+        let movements = Day01::parse("R2, L3, L4").unwrap();
+        let route = Route::record(&movements).unwrap();
+        assert_eq!(route.steps().len(), 9);
+        assert_eq!(
+            route.steps()[2],
+            Step {
+                movement: 1,
+                heading: Direction::North,
+                coordinates: Coordinates {
+                    pos_x: 2,
+                    pos_y: -1
+                },
+            }
+        );
+        assert_eq!(route.end().origin_distance(), part1(&movements).unwrap());
+        let bbox = route.bounding_box();
+        assert_eq!((bbox.min.pos_x, bbox.min.pos_y), (-2, -3));
+        assert_eq!((bbox.width(), bbox.height()), (5, 4));
+    }
+
+    #[test]
+    fn revisits() {
+        // Two laps around a square, the second one wider
+        let movements = Day01::parse("R2, R2, R2, R4, R2, R2, R2").unwrap();
+        let route = Route::record(&movements).unwrap();
+        let revisits = route.revisits().collect::<Vec<_>>();
+        let origin = Coordinates::default();
+        assert_eq!(
+            revisits[0],
+            Revisit {
+                coordinates: origin,
+                first_step: 0,
+                step: 8,
+                visits: 2,
+            }
+        );
+        assert_eq!(part2(&movements), Ok(Some(0)));
+        assert_eq!(revisits.len(), 4);
+        assert_eq!(revisits[3].visits, 3);
+        assert_eq!(
+            route.most_visited(2),
+            vec![(origin, 3), (Coordinates { pos_x: 1, pos_y: 0 }, 2)]
+        );
+    }
+}
@@ -1,39 +1,244 @@
-use clap::{crate_description, App, Arg};
-use day01::{part1, part2, Movement};
-use std::fs::read_to_string;
+use aoc::answers::Answers;
+use aoc::input::{read_input, InputCache, YEAR};
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, print_error, run,
+    stats_arg, Format, Part, Report,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::{Error, Solution};
+use clap::{crate_description, value_t_or_exit, App, Arg};
+use day01::stream::walk_from;
+use day01::topology::{Cubic, Hexagonal, Octagonal, Walk};
+use day01::{part1, render, sweep, Day01, Movement, Route};
+use std::fs::{write, File};
+use std::io::BufReader;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
 fn main() {
     let args = App::new(crate_description!())
         .arg(
             Arg::with_name("INPUT")
-                .help("Sets the input file to use")
-                .required(true)
+                .help("Sets the input file to use [default: cached input]")
                 .index(1),
         )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
+        .arg(
+            Arg::with_name("ROUTE")
+                .long("route")
+                .help("Prints every block walked and its bounding box"),
+        )
+        .arg(
+            Arg::with_name("REVISITS")
+                .long("revisits")
+                .help("Prints every block visited again and the most visited"),
+        )
+        .arg(
+            Arg::with_name("RENDER")
+                .long("render")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Draws the walk to an SVG or PPM file"),
+        )
+        .arg(
+            Arg::with_name("SCALE")
+                .long("scale")
+                .takes_value(true)
+                .default_value("4")
+                .help("Pixels per block in PPM files, at least 1"),
+        )
+        .arg(
+            Arg::with_name("GRID")
+                .long("grid")
+                .takes_value(true)
+                .possible_values(&["square", "octagonal", "hexagonal", "cubic"])
+                .help("Walks on a different grid"),
+        )
+        .arg(
+            Arg::with_name("STREAM")
+                .long("stream")
+                .conflicts_with_all(&["ROUTE", "REVISITS", "RENDER", "GRID"])
+                .help("Reads movements as they are walked"),
+        )
         .get_matches();
 
-    println!(crate_description!());
-    let movements = read_input(args.value_of("INPUT").unwrap());
-    println!("Part 1: {}", part1(&movements));
-    if let Some(answer) = part2(&movements) {
-        println!("Part 2: {}", answer);
-    } else {
-        println!("Part 2: not found");
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+    if args.is_present("STREAM") {
+        let filename = match args.value_of("INPUT") {
+            Some(filename) => Ok(PathBuf::from(filename)),
+            None => InputCache::from_env().resolve(YEAR, Day01::DAY),
+        }
+        .unwrap_or_else(exit_with_error(Day01::DAY, format, 2));
+        let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+            .unwrap_or_else(exit_with_error(Day01::DAY, format, 2));
+        let reports = answers.check(vec![
+            Report::time(Day01::DAY, Part::One, || {
+                stream(&filename, format, |movements| {
+                    part1(movements).map(Some)
+                })
+            }),
+            Report::time(Day01::DAY, Part::Two, || {
+                stream(&filename, format, |movements| sweep::part2(movements))
+            }),
+        ]);
+        for report in &reports {
+            report.print(format, stats);
+        }
+        exit(exit_code(&reports));
+    }
+
+    let (contents, filename) = read_input(args.value_of("INPUT"), Day01::DAY)
+        .unwrap_or_else(exit_with_error(Day01::DAY, format, 2));
+    let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+        .unwrap_or_else(exit_with_error(Day01::DAY, format, 2));
+
+    if ["ROUTE", "REVISITS", "RENDER"]
+        .iter()
+        .any(|&name| args.is_present(name))
+    {
+        let movements = Day01::parse(&contents)
+            .unwrap_or_else(exit_with_error(Day01::DAY, format, 3));
+        let route = Route::record(&movements).unwrap_or_else(exit_with_error(
+            Day01::DAY,
+            format,
+            3,
+        ));
+        if let Some(filename) = args.value_of("RENDER") {
+            let scale = value_t_or_exit!(args.value_of("SCALE"), NonZeroUsize);
+            render_route(&route, Path::new(filename), scale, format);
+        }
+        if args.is_present("ROUTE") {
+            print_route(&route, format);
+        }
+        if args.is_present("REVISITS") {
+            print_revisits(&route, format);
+        }
+        exit(0);
+    }
+
+    let parts = [Part::One, Part::Two];
+    let reports = match args.value_of("GRID") {
+        Some("octagonal") => run::<Walk<Octagonal>>(&contents, &parts),
+        Some("hexagonal") => run::<Walk<Hexagonal>>(&contents, &parts),
+        Some("cubic") => run::<Walk<Cubic>>(&contents, &parts),
+        _ => answers.check(run::<Day01>(&contents, &parts)),
+    };
+    for report in &reports {
+        report.print(format, stats);
+    }
+    exit(exit_code(&reports));
+}
+
+/// Walks the movements read from `filename` without reading it all first.
+fn stream<T, F>(filename: &Path, format: Format, walk: F) -> Result<T, Error>
+where
+    F: FnOnce(&mut dyn Iterator<Item = Movement>) -> Result<T, Error>,
+{
+    let file = File::open(filename)
+        .map_err(|err| {
+            format!("Failed to read file '{}': {}", filename.display(), err)
+        })
+        .unwrap_or_else(exit_with_error(Day01::DAY, format, 2));
+    walk_from(BufReader::new(file), walk).map_err(Error::from)?
+}
+
+fn print_route(route: &Route, format: Format) {
+    if format == Format::Json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "route": route,
+                "bounding_box": route.bounding_box(),
+            })
+        );
+        return;
+    }
+    let start = route.start();
+    println!("Start: {}, {}", start.pos_x, start.pos_y);
+    for step in route.steps() {
+        println!(
+            "Movement {}, heading {:?}: {}, {}",
+            step.movement + 1,
+            step.heading,
+            step.coordinates.pos_x,
+            step.coordinates.pos_y
+        );
+    }
+    let bbox = route.bounding_box();
+    println!(
+        "Bounding box: {}, {} to {}, {} ({} x {} blocks)",
+        bbox.min.pos_x,
+        bbox.min.pos_y,
+        bbox.max.pos_x,
+        bbox.max.pos_y,
+        bbox.width(),
+        bbox.height()
+    );
+}
+
+/// Number of locations listed as the most visited.
+const MOST_VISITED: usize = 5;
+
+fn print_revisits(route: &Route, format: Format) {
+    let most_visited = route.most_visited(MOST_VISITED);
+    if format == Format::Json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "revisits": route.revisits().collect::<Vec<_>>(),
+                "most_visited": most_visited,
+            })
+        );
+        return;
+    }
+    for revisit in route.revisits() {
+        println!(
+            "Step {}: back at {}, {} first visited at step {} ({} visits)",
+            revisit.step,
+            revisit.coordinates.pos_x,
+            revisit.coordinates.pos_y,
+            revisit.first_step,
+            revisit.visits
+        );
+    }
+    println!("Most visited:");
+    for (coordinates, visits) in most_visited {
+        println!(
+            "  {}, {}: {} visits",
+            coordinates.pos_x, coordinates.pos_y, visits
+        );
     }
 }
 
-fn read_input(filename: &str) -> Vec<Movement> {
-    let input = read_to_string(filename).unwrap_or_else(|err| {
-        println!("Failed to read file '{}': {}", filename, err.to_string());
-        exit(2);
-    });
-    input
-        .split(',')
-        .map(|s| s.trim().parse())
-        .collect::<Result<_, _>>()
-        .unwrap_or_else(|err| {
-            println!("Failed to parse input: {}", err);
-            exit(3);
+fn render_route(
+    route: &Route,
+    filename: &Path,
+    scale: NonZeroUsize,
+    format: Format,
+) {
+    let image = match filename.extension().and_then(|ext| ext.to_str()) {
+        Some("svg") => render::svg(route).into_bytes(),
+        Some("ppm") => render::ppm(route, scale)
+            .unwrap_or_else(exit_with_error(Day01::DAY, format, 1)),
+        _ => {
+            let err = format!("Unknown image format: '{}'", filename.display());
+            print_error(Day01::DAY, format, &err);
+            exit(1);
+        }
+    };
+    write(filename, image)
+        .map_err(|err| {
+            format!("Failed to write file '{}': {}", filename.display(), err)
         })
+        .unwrap_or_else(exit_with_error(Day01::DAY, format, 2));
 }
@@ -0,0 +1,173 @@
+//! Pictures of a walk on the city grid, marking the origin, the final
+//! position and every point visited more than once.
+
+use crate::{Coordinates, Route};
+use std::fmt::{Display, Formatter};
+use std::num::NonZeroUsize;
+
+type Colour = [u8; 3];
+
+const BACKGROUND: Colour = [255, 255, 255];
+const PATH: Colour = [0, 0, 0];
+const REVISITED: Colour = [255, 140, 0];
+const ORIGIN: Colour = [0, 160, 0];
+const END: Colour = [200, 0, 0];
+
+/// Blocks left blank around the walk.
+const MARGIN: i64 = 1;
+
+/// Most pixels drawn in a PPM image, three bytes each.
+pub const MAX_PIXELS: u128 = 25_000_000;
+
+/// The size of an image with more than `MAX_PIXELS` pixels.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TooLarge {
+    pub width: u128,
+    pub height: u128,
+}
+
+impl Display for TooLarge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Image of {} x {} pixels is larger than {} pixels",
+            self.width, self.height, MAX_PIXELS
+        )
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+fn revisited(route: &Route) -> Vec<Coordinates> {
+    route
+        .revisits()
+        .filter(|revisit| revisit.visits == 2)
+        .map(|revisit| revisit.coordinates)
+        .collect()
+}
+
+fn hex(colour: Colour) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+/// Draws the walk as an SVG image with one unit per block.
+pub fn svg(route: &Route) -> String {
+    let bbox = route.bounding_box();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        bbox.min.pos_x - MARGIN,
+        bbox.min.pos_y - MARGIN,
+        bbox.width() + 2 * MARGIN - 1,
+        bbox.height() + 2 * MARGIN - 1,
+    );
+    svg.push_str(&format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        bbox.min.pos_x - MARGIN,
+        bbox.min.pos_y - MARGIN,
+        hex(BACKGROUND)
+    ));
+    let points = route
+        .points()
+        .map(|point| format!("{},{}", point.pos_x, point.pos_y))
+        .collect::<Vec<_>>();
+    svg.push_str(&format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" \
+         stroke-width=\"0.2\" stroke-linejoin=\"round\"/>\n",
+        points.join(" "),
+        hex(PATH)
+    ));
+    let markers = revisited(route)
+        .into_iter()
+        .map(|point| (point, 0.3, REVISITED))
+        .chain(vec![(route.start(), 0.5, ORIGIN), (route.end(), 0.5, END)]);
+    for (point, radius, colour) in markers {
+        svg.push_str(&format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+            point.pos_x,
+            point.pos_y,
+            radius,
+            hex(colour)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Draws the walk as a binary portable pixmap (PPM) with `scale` pixels per
+/// block, unless it would have more than `MAX_PIXELS` pixels.
+pub fn ppm(route: &Route, scale: NonZeroUsize) -> Result<Vec<u8>, TooLarge> {
+    let scale = scale.get();
+    let bbox = route.bounding_box();
+    // A side is at most 2^64 blocks of 2^64 pixels, but the area may not fit
+    let size = |blocks: i64| (blocks + 2 * MARGIN) as u128 * scale as u128;
+    let (width, height) = (size(bbox.width()), size(bbox.height()));
+    if width
+        .checked_mul(height)
+        .is_none_or(|area| area > MAX_PIXELS)
+    {
+        return Err(TooLarge { width, height });
+    }
+    let (width, height) = (width as usize, height as usize);
+    let mut pixels = vec![BACKGROUND; width * height];
+
+    let mut paint = |point: Coordinates, colour: Colour| {
+        let col = (point.pos_x - bbox.min.pos_x + MARGIN) as usize;
+        let row = (point.pos_y - bbox.min.pos_y + MARGIN) as usize;
+        for y in row * scale..(row + 1) * scale {
+            for x in col * scale..(col + 1) * scale {
+                pixels[y * width + x] = colour;
+            }
+        }
+    };
+    for point in route.points() {
+        paint(point, PATH);
+    }
+    for point in revisited(route) {
+        paint(point, REVISITED);
+    }
+    paint(route.start(), ORIGIN);
+    paint(route.end(), END);
+
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    image.extend(pixels.iter().flatten());
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day01;
+    use aoc::Solution;
+
+    #[test]
+    fn pictures() {
+        let movements = Day01::parse("R8, R4, R4, R8").unwrap();
+        let route = Route::record(&movements).unwrap();
+        assert_eq!(revisited(&route), vec![Coordinates { pos_x: 4, pos_y: 0 }]);
+
+        let svg = svg(&route);
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(svg.matches("<circle").count(), 3);
+
+        // 9 x 9 blocks plus margins at 2 pixels per block
+        let scale = |scale| NonZeroUsize::new(scale).unwrap();
+        let image = ppm(&route, scale(2)).unwrap();
+        assert!(image.starts_with(b"P6\n22 22\n255\n"));
+        assert_eq!(image.len(), "P6\n22 22\n255\n".len() + 22 * 22 * 3);
+        assert!("0".parse::<NonZeroUsize>().is_err());
+        assert_eq!(
+            ppm(&route, NonZeroUsize::MAX),
+            Err(TooLarge {
+                width: 11 * usize::MAX as u128,
+                height: 11 * usize::MAX as u128,
+            })
+        );
+
+        let movements = Day01::parse("R800000, R100000").unwrap();
+        let route = Route::record(&movements).unwrap();
+        assert!(ppm(&route, NonZeroUsize::MIN).is_err());
+        let movements = Day01::parse("R2, L2000000000").unwrap();
+        let err = Route::record(&movements).err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 1, column: 5 });
+    }
+}
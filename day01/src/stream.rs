@@ -0,0 +1,295 @@
+//! Reads movements one at a time from any reader, so that a walk can be
+//! followed without holding its instructions in memory. Movements are
+//! separated by commas, whitespace or both, with at most one comma between
+//! two movements.
+
+use crate::Movement;
+use aoc::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead, ErrorKind};
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    /// A token that is not a movement, with its 1-based index among the
+    /// tokens read and the 0-based byte offset where it starts
+    Token {
+        index: usize,
+        offset: usize,
+        error: Error,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Failed to read input: {}", err),
+            Self::Token {
+                index,
+                offset,
+                error,
+            } => write!(f, "{} (token {}, byte {})", error, index, offset),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    /// Read failures are not found at any token, so they are placed at the
+    /// start of the input.
+    fn from(err: ParseError) -> Self {
+        match err {
+            ParseError::Io(_) => Error::format(1, "", &err),
+            ParseError::Token { error, .. } => error,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Location {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    Start,
+    AfterToken,
+    AfterComma,
+}
+
+/// An iterator over the movements read from a `BufRead`, which stops after
+/// the first error.
+pub struct Movements<R> {
+    reader: R,
+    /// The bytes of the token being read
+    token: Vec<u8>,
+    location: Location,
+    state: State,
+    tokens: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Movements<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            token: Vec::new(),
+            location: Location {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            state: State::Start,
+            tokens: 0,
+            done: false,
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, ParseError> {
+        let byte = loop {
+            match self.reader.fill_buf() {
+                Ok(buf) => break buf.first().copied(),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(ParseError::Io(err)),
+            }
+        };
+        if let Some(byte) = byte {
+            self.reader.consume(1);
+            self.location.offset += 1;
+            if byte == b'\n' {
+                self.location.line += 1;
+                self.location.column = 1;
+            } else if byte & 0xc0 != 0x80 {
+                // Columns count characters, so continuation bytes of
+                // multi-byte characters don't move to the next column
+                self.location.column += 1;
+            }
+        }
+        Ok(byte)
+    }
+
+    fn error(
+        &self,
+        location: Location,
+        index: usize,
+        error: Error,
+    ) -> ParseError {
+        ParseError::Token {
+            index,
+            offset: location.offset,
+            error: error.shifted(location.column - 1).at_line(location.line),
+        }
+    }
+
+    fn missing(&self, location: Location, text: &str) -> ParseError {
+        let error = Error::format(1, text, "Missing movement");
+        self.error(location, self.tokens + 1, error)
+    }
+
+    fn read_movement(&mut self) -> Result<Option<Movement>, ParseError> {
+        let mut start = None;
+        loop {
+            let location = self.location;
+            let byte = match self.next_byte()? {
+                Some(byte) => byte,
+                None => break,
+            };
+            if byte != b',' && !byte.is_ascii_whitespace() {
+                if start.is_none() {
+                    start = Some(location);
+                    self.state = State::AfterToken;
+                    self.token.clear();
+                }
+                self.token.push(byte);
+            } else if start.is_some() {
+                self.state = if byte == b',' {
+                    State::AfterComma
+                } else {
+                    State::AfterToken
+                };
+                break;
+            } else if byte == b',' {
+                if self.state != State::AfterToken {
+                    return Err(self.missing(location, ","));
+                }
+                self.state = State::AfterComma;
+            }
+        }
+
+        let start = match start {
+            Some(start) => start,
+            None if self.state == State::AfterComma => {
+                return Err(self.missing(self.location, ""));
+            }
+            None => return Ok(None),
+        };
+        self.tokens += 1;
+        let text = std::str::from_utf8(&self.token).map_err(|_| {
+            let text = String::from_utf8_lossy(&self.token);
+            let error = Error::format(1, text, "Invalid UTF-8");
+            self.error(start, self.tokens, error)
+        })?;
+        let mut movement: Movement = text
+            .parse()
+            .map_err(|error| self.error(start, self.tokens, error))?;
+        movement.location = aoc::Position {
+            line: start.line,
+            column: start.column,
+        };
+        Ok(Some(movement))
+    }
+}
+
+impl<R: BufRead> Iterator for Movements<R> {
+    type Item = Result<Movement, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_movement().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+/// Passes the movements read from `reader` to `walk` as they are read.
+/// Reading stops at the first error, which is returned instead of what
+/// `walk` returns.
+pub fn walk_from<R, T, F>(reader: R, walk: F) -> Result<T, ParseError>
+where
+    R: BufRead,
+    F: FnOnce(&mut dyn Iterator<Item = Movement>) -> T,
+{
+    let mut error = None;
+    let result = {
+        let mut movements = Movements::new(reader)
+            .map_while(|result| result.map_err(|err| error = Some(err)).ok());
+        walk(&mut movements)
+    };
+    match error {
+        Some(err) => Err(err),
+        None => Ok(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1, part2};
+
+    fn token_error(input: &str) -> (usize, usize, Error) {
+        match Movements::new(input.as_bytes()).find_map(Result::err) {
+            Some(ParseError::Token {
+                index,
+                offset,
+                error,
+            }) => (index, offset, error),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn separators() {
+        let input = "R2,L3\n\tR2  L2 ,\n R1";
+        let movements = Movements::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(movements.len(), 5);
+        assert_eq!(movements[4].location, aoc::Position { line: 3, column: 2 });
+        assert_eq!(
+            walk_from(input.as_bytes(), |movements| part1(movements)).unwrap(),
+            Ok(10)
+        );
+    }
+
+    #[test]
+    fn bad_tokens() {
+        let (index, offset, error) = token_error("R2, L3, L-4");
+        assert_eq!((index, offset), (3, 8));
+        assert_eq!(
+            error.position(),
+            aoc::Position {
+                line: 1,
+                column: 10
+            }
+        );
+        assert_eq!(error.reason(), "Negative number of steps");
+        let (_, _, error) = token_error("L-99999999999999999999");
+        assert_eq!(error.reason(), "Negative number of steps");
+        let err = Movements::new("L-4".as_bytes()).find_map(Result::err);
+        assert_eq!(Error::from(err.unwrap()).position().column, 2);
+        let (_, _, error) = token_error("L-");
+        assert!(error.reason().starts_with("Invalid number of steps"));
+
+        let (index, offset, error) = token_error("R2,\n,L3");
+        assert_eq!((index, offset), (2, 4));
+        assert_eq!(error.position(), aoc::Position { line: 2, column: 1 });
+        assert_eq!(error.reason(), "Missing movement");
+
+        let (index, offset, error) = token_error("L1 ü2, R3");
+        assert_eq!((index, offset), (2, 3));
+        assert_eq!(error.position(), aoc::Position { line: 1, column: 4 });
+        assert_eq!(error.text(), "ü");
+
+        let (index, _, error) = token_error("R2, L3,");
+        assert_eq!(index, 3);
+        assert_eq!(error.position(), aoc::Position { line: 1, column: 8 });
+    }
+
+    #[test]
+    fn reads_only_what_is_walked() {
+        let input = "R8, R4, R4, R8, X1";
+        let walk = walk_from(input.as_bytes(), |movements| part1(movements));
+        assert!(matches!(walk, Err(ParseError::Token { index: 5, .. })));
+
+        // The first block visited twice is found before the bad token
+        let walk = walk_from(input.as_bytes(), |movements| part2(movements));
+        assert_eq!(walk.unwrap(), Ok(Some(4)));
+    }
+}
@@ -0,0 +1,295 @@
+//! Finds the first block visited twice from the segments walked by each
+//! movement instead of one block at a time, so that the time and memory
+//! taken don't depend on the number of steps.
+
+use crate::{Coordinates, Direction, Movement};
+use aoc::Error;
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+
+/// The blocks walked by one movement, from the first block after turning
+/// to the last one. Segments are closed, so in a walk that never visits a
+/// block twice every block is in exactly one segment.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    start: Coordinates,
+    end: Coordinates,
+}
+
+impl Segment {
+    fn min(&self) -> Coordinates {
+        Coordinates {
+            pos_x: self.start.pos_x.min(self.end.pos_x),
+            pos_y: self.start.pos_y.min(self.end.pos_y),
+        }
+    }
+
+    fn max(&self) -> Coordinates {
+        Coordinates {
+            pos_x: self.start.pos_x.max(self.end.pos_x),
+            pos_y: self.start.pos_y.max(self.end.pos_y),
+        }
+    }
+
+    /// Single blocks count as horizontal segments.
+    fn is_horizontal(&self) -> bool {
+        self.start.pos_y == self.end.pos_y
+    }
+
+    /// The block of this segment nearest to its start that is also in
+    /// `other`, if any.
+    fn first_common(&self, other: &Self) -> Option<Coordinates> {
+        // Both segments are axis-aligned, so their common blocks are the
+        // intersection of their bounding boxes
+        let (min_a, max_a, min_b, max_b) =
+            (self.min(), self.max(), other.min(), other.max());
+        let min = Coordinates {
+            pos_x: min_a.pos_x.max(min_b.pos_x),
+            pos_y: min_a.pos_y.max(min_b.pos_y),
+        };
+        let max = Coordinates {
+            pos_x: max_a.pos_x.min(max_b.pos_x),
+            pos_y: max_a.pos_y.min(max_b.pos_y),
+        };
+        if min.pos_x > max.pos_x || min.pos_y > max.pos_y {
+            return None;
+        }
+        Some(Coordinates {
+            pos_x: self.start.pos_x.clamp(min.pos_x, max.pos_x),
+            pos_y: self.start.pos_y.clamp(min.pos_y, max.pos_y),
+        })
+    }
+}
+
+/// The origin as a single-block segment followed by the segment of every
+/// movement with at least one step.
+fn segments<I>(movements: I) -> Result<Vec<Segment>, Error>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Movement>,
+{
+    let origin = Coordinates::default();
+    let mut segments = vec![Segment {
+        start: origin,
+        end: origin,
+    }];
+    let mut direction = Direction::default();
+    let mut current = origin;
+    for movement in movements {
+        let movement = movement.borrow();
+        direction = direction
+            .turn(&movement.turn)
+            .ok_or_else(|| movement.unsupported_turn("square"))?;
+        let end = current.jump(&direction, i64::from(movement.steps));
+        if movement.steps > 0 {
+            let start = current.step(&direction);
+            segments.push(Segment { start, end });
+        }
+        current = end;
+    }
+    Ok(segments)
+}
+
+/// The indices of the horizontal segments crossing the sweep line by row,
+/// with the smallest index over a range of rows at hand.
+struct ActiveRows {
+    /// Rows of all horizontal segments, in order
+    rows: Vec<i64>,
+    indices: Vec<BTreeSet<usize>>,
+    /// Segment tree of the smallest index in each row, leaves last
+    tree: Vec<usize>,
+}
+
+impl ActiveRows {
+    fn new(rows: Vec<i64>) -> Self {
+        Self {
+            indices: vec![BTreeSet::new(); rows.len()],
+            tree: vec![usize::MAX; 2 * rows.len()],
+            rows,
+        }
+    }
+
+    fn update<F>(&mut self, row: i64, change: F)
+    where
+        F: FnOnce(&mut BTreeSet<usize>),
+    {
+        let leaf = self.rows.binary_search(&row).unwrap();
+        change(&mut self.indices[leaf]);
+        let mut node = leaf + self.rows.len();
+        self.tree[node] = self.indices[leaf]
+            .iter()
+            .next()
+            .copied()
+            .unwrap_or(usize::MAX);
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = self.tree[2 * node].min(self.tree[2 * node + 1]);
+        }
+    }
+
+    /// The smallest index of the segments in rows `from` to `to`.
+    fn min(&self, from: i64, to: i64) -> Option<usize> {
+        let len = self.rows.len();
+        let mut low = self.rows.partition_point(|&row| row < from) + len;
+        let mut high = self.rows.partition_point(|&row| row <= to) + len;
+        let mut min = usize::MAX;
+        while low < high {
+            if low % 2 == 1 {
+                min = min.min(self.tree[low]);
+                low += 1;
+            }
+            if high % 2 == 1 {
+                high -= 1;
+                min = min.min(self.tree[high]);
+            }
+            low /= 2;
+            high /= 2;
+        }
+        Some(min).filter(|&min| min != usize::MAX)
+    }
+}
+
+/// The index of the first segment with a block in common with an earlier
+/// one, as long as either of the two is horizontal. A vertical line is swept
+/// across the grid once, so this takes O(n log n) time.
+///
+/// Each segment is compared with the earliest segment on the line where it
+/// enters, and the later of the two is a candidate; the smallest candidate
+/// is the first segment that reaches an earlier one.
+fn first_horizontal_crossing(segments: &[Segment]) -> Option<usize> {
+    let mut rows = segments
+        .iter()
+        .filter(|segment| segment.is_horizontal())
+        .map(|segment| segment.start.pos_y)
+        .collect::<Vec<_>>();
+    rows.sort_unstable();
+    rows.dedup();
+
+    // Events at the same column are ordered so that horizontal segments
+    // are active while segments on their ends are checked
+    const ENTER: u8 = 0;
+    const CHECK: u8 = 1;
+    const LEAVE: u8 = 2;
+    let mut events = Vec::with_capacity(2 * segments.len());
+    for (index, segment) in segments.iter().enumerate() {
+        let (min, max) = (segment.min(), segment.max());
+        if segment.is_horizontal() {
+            events.push((min.pos_x, ENTER, min.pos_y, min.pos_y, index));
+            events.push((max.pos_x, LEAVE, min.pos_y, min.pos_y, index));
+        } else {
+            events.push((min.pos_x, CHECK, min.pos_y, max.pos_y, index));
+        }
+    }
+    events.sort_unstable();
+
+    let mut active = ActiveRows::new(rows);
+    let mut first: Option<usize> = None;
+    for (_, event, from, to, index) in events {
+        if event != LEAVE {
+            if let Some(earliest) = active.min(from, to) {
+                let later = index.max(earliest);
+                first = Some(first.map_or(later, |first| first.min(later)));
+            }
+        }
+        match event {
+            ENTER => active.update(from, |indices| {
+                indices.insert(index);
+            }),
+            LEAVE => active.update(from, |indices| {
+                indices.remove(&index);
+            }),
+            _ => {}
+        }
+    }
+    first
+}
+
+/// The index of the first segment with a block in common with an earlier
+/// one, found by sweeping the walk as it is and transposed, so that segments
+/// on the same column are compared as horizontal ones.
+fn first_crossing(segments: &[Segment]) -> Option<usize> {
+    let transpose = |point: Coordinates| Coordinates {
+        pos_x: point.pos_y,
+        pos_y: point.pos_x,
+    };
+    let transposed = segments
+        .iter()
+        .map(|segment| Segment {
+            start: transpose(segment.start),
+            end: transpose(segment.end),
+        })
+        .collect::<Vec<_>>();
+    match (
+        first_horizontal_crossing(segments),
+        first_horizontal_crossing(&transposed),
+    ) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Returns the distance from the origin to the first block visited twice,
+/// in O(n log n) time for n movements.
+pub fn part2<I>(movements: I) -> Result<Option<i64>, Error>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Movement>,
+{
+    let segments = segments(movements)?;
+    let last = match first_crossing(&segments) {
+        Some(last) => last,
+        None => return Ok(None),
+    };
+    let segment = segments[last];
+    Ok(segments[..last]
+        .iter()
+        .filter_map(|earlier| segment.first_common(earlier))
+        .min_by_key(|point| point.distance(&segment.start))
+        .map(|point| point.origin_distance()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day01;
+    use aoc::Solution;
+
+    /// Pseudo-random walks with short movements, including some with no
+    /// steps, for the block-by-block `part2` to check against.
+    fn random_walks() -> impl Iterator<Item = String> {
+        let mut seed = 2016_u64;
+        let mut next = move |modulo: u64| {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (seed >> 33) % modulo
+        };
+        (0..500).map(move |_| {
+            let len = 1 + next(30);
+            (0..len)
+                .map(|_| {
+                    let turn = if next(2) == 0 { 'L' } else { 'R' };
+                    format!("{}{}", turn, next(8))
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    }
+
+    #[test]
+    fn matches_step_by_step_walk() {
+        for walk in random_walks() {
+            let movements = Day01::parse(&walk).unwrap();
+            assert_eq!(part2(&movements), crate::part2(&movements), "{}", walk);
+        }
+    }
+
+    #[test]
+    fn long_movements() {
+        // Crosses the second movement far beyond the range of i32, with too
+        // many steps to walk one by one
+        let walk = "R2000000000, L2000000000, L10, L5, L20";
+        let movements = Day01::parse(walk).unwrap();
+        assert_eq!(part2(&movements), Ok(Some(3_999_999_995)));
+    }
+}
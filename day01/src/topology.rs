@@ -0,0 +1,368 @@
+//! Walks on other grids than the city's square grid. Every grid has its own
+//! headings, turns and distance metric, and is walked with the same
+//! movements as the puzzle.
+
+use crate::{Coordinates, Direction, Movement, Turn};
+use aoc::{Error, Solution};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+pub trait Topology {
+    type Heading: Copy + Debug + Default;
+    type Point: Copy + Debug + Default + Eq + Hash;
+
+    /// The name of the grid, for errors.
+    const NAME: &'static str;
+
+    /// The heading after `turn`, or `None` if the grid has no such turn.
+    fn turn(heading: Self::Heading, turn: Turn) -> Option<Self::Heading>;
+
+    fn step(
+        point: Self::Point,
+        heading: Self::Heading,
+        steps: i64,
+    ) -> Self::Point;
+
+    /// The fewest single steps that lead from the origin to `point`.
+    fn origin_distance(point: Self::Point) -> i64;
+}
+
+fn turn<T: Topology>(
+    heading: T::Heading,
+    movement: &Movement,
+) -> Result<T::Heading, Error> {
+    T::turn(heading, movement.turn)
+        .ok_or_else(|| movement.unsupported_turn(T::NAME))
+}
+
+/// The point where a walk from the origin ends.
+pub fn destination<T, I>(movements: I) -> Result<T::Point, Error>
+where
+    T: Topology,
+    I: IntoIterator,
+    I::Item: Borrow<Movement>,
+{
+    let mut heading = T::Heading::default();
+    let mut point = T::Point::default();
+    for movement in movements {
+        let movement = movement.borrow();
+        heading = turn::<T>(heading, movement)?;
+        point = T::step(point, heading, i64::from(movement.steps));
+    }
+    Ok(point)
+}
+
+/// The distance from the origin to where a walk ends.
+pub fn distance<T, I>(movements: I) -> Result<i64, Error>
+where
+    T: Topology,
+    I: IntoIterator,
+    I::Item: Borrow<Movement>,
+{
+    destination::<T, _>(movements).map(T::origin_distance)
+}
+
+/// The distance from the origin to the first point visited twice, walking
+/// one step at a time.
+pub fn first_revisit<T, I>(movements: I) -> Result<Option<i64>, Error>
+where
+    T: Topology,
+    I: IntoIterator,
+    I::Item: Borrow<Movement>,
+{
+    let mut heading = T::Heading::default();
+    let mut point = T::Point::default();
+    let mut visited = HashSet::new();
+    visited.insert(point);
+    for movement in movements {
+        let movement = movement.borrow();
+        heading = turn::<T>(heading, movement)?;
+        for _ in 0..movement.steps {
+            point = T::step(point, heading, 1);
+            if !visited.insert(point) {
+                return Ok(Some(T::origin_distance(point)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// The puzzle on another grid: part 1 is the distance to where the walk
+/// ends and part 2 the distance to the first point visited twice.
+pub struct Walk<T>(PhantomData<T>);
+
+impl<T: Topology> Solution for Walk<T> {
+    type Input = Vec<Movement>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    const DAY: u32 = 1;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        crate::Day01::parse(input)
+    }
+
+    fn part1(movements: &Self::Input) -> Result<Option<i64>, Error> {
+        distance::<T, _>(movements).map(Some)
+    }
+
+    fn part2(movements: &Self::Input) -> Result<Option<i64>, Error> {
+        first_revisit::<T, _>(movements)
+    }
+}
+
+/// The city grid, with Manhattan distances. Turns are quarter turns.
+pub struct Square;
+
+impl Topology for Square {
+    type Heading = Direction;
+    type Point = Coordinates;
+
+    const NAME: &'static str = "square";
+
+    fn turn(heading: Direction, turn: Turn) -> Option<Direction> {
+        heading.turn(&turn)
+    }
+
+    fn step(point: Coordinates, heading: Direction, steps: i64) -> Coordinates {
+        point.jump(&heading, steps)
+    }
+
+    fn origin_distance(point: Coordinates) -> i64 {
+        point.origin_distance()
+    }
+}
+
+/// Eighths of a full turn clockwise from north.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Bearing(u8);
+
+/// A square grid that can also be walked diagonally, where every point
+/// around another one is a single step away. Turns are eighth turns.
+pub struct Octagonal;
+
+impl Topology for Octagonal {
+    type Heading = Bearing;
+    type Point = Coordinates;
+
+    const NAME: &'static str = "octagonal";
+
+    fn turn(heading: Bearing, turn: Turn) -> Option<Bearing> {
+        let eighths = match turn {
+            Turn::Forward => 0,
+            Turn::Right => 1,
+            Turn::Back => 4,
+            Turn::Left => 7,
+            Turn::Up | Turn::Down => return None,
+        };
+        Some(Bearing((heading.0 + eighths) % 8))
+    }
+
+    fn step(point: Coordinates, heading: Bearing, steps: i64) -> Coordinates {
+        const OFFSETS: [(i64, i64); 8] = [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+        let (dx, dy) = OFFSETS[usize::from(heading.0)];
+        Coordinates {
+            pos_x: point.pos_x + dx * steps,
+            pos_y: point.pos_y + dy * steps,
+        }
+    }
+
+    fn origin_distance(point: Coordinates) -> i64 {
+        point.pos_x.abs().max(point.pos_y.abs())
+    }
+}
+
+/// Sixths of a full turn clockwise from north.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HexBearing(u8);
+
+/// Axial coordinates of a flat-topped hexagon: `q` grows towards the
+/// north-east and `r` towards the south.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct HexCoordinates {
+    pub q: i64,
+    pub r: i64,
+}
+
+/// A grid of hexagons. Turns are sixth turns.
+pub struct Hexagonal;
+
+impl Topology for Hexagonal {
+    type Heading = HexBearing;
+    type Point = HexCoordinates;
+
+    const NAME: &'static str = "hexagonal";
+
+    fn turn(heading: HexBearing, turn: Turn) -> Option<HexBearing> {
+        let sixths = match turn {
+            Turn::Forward => 0,
+            Turn::Right => 1,
+            Turn::Back => 3,
+            Turn::Left => 5,
+            Turn::Up | Turn::Down => return None,
+        };
+        Some(HexBearing((heading.0 + sixths) % 6))
+    }
+
+    fn step(
+        point: HexCoordinates,
+        heading: HexBearing,
+        steps: i64,
+    ) -> HexCoordinates {
+        const OFFSETS: [(i64, i64); 6] =
+            [(0, -1), (1, -1), (1, 0), (0, 1), (-1, 1), (-1, 0)];
+        let (dq, dr) = OFFSETS[usize::from(heading.0)];
+        HexCoordinates {
+            q: point.q + dq * steps,
+            r: point.r + dr * steps,
+        }
+    }
+
+    fn origin_distance(point: HexCoordinates) -> i64 {
+        (point.q.abs() + point.r.abs() + (point.q + point.r).abs()) / 2
+    }
+}
+
+type Vector = [i64; 3];
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn negate(a: Vector) -> Vector {
+    [-a[0], -a[1], -a[2]]
+}
+
+/// Where a walker in three dimensions faces and which way is up for them.
+/// Vectors are `[east, south, up]`, so a walker starts facing north with up
+/// above them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Orientation {
+    forward: Vector,
+    up: Vector,
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Self {
+            forward: [0, -1, 0],
+            up: [0, 0, 1],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CubeCoordinates {
+    pub pos_x: i64,
+    pub pos_y: i64,
+    pub pos_z: i64,
+}
+
+/// A grid of cubes with Manhattan distances. Left and right turns are
+/// around the walker's up axis, while up and down turns pitch the walker.
+pub struct Cubic;
+
+impl Topology for Cubic {
+    type Heading = Orientation;
+    type Point = CubeCoordinates;
+
+    const NAME: &'static str = "cubic";
+
+    fn turn(heading: Orientation, turn: Turn) -> Option<Orientation> {
+        let Orientation { forward, up } = heading;
+        // With south as the second axis, vectors are left-handed
+        let (forward, up) = match turn {
+            Turn::Forward => (forward, up),
+            Turn::Left => (cross(forward, up), up),
+            Turn::Right => (cross(up, forward), up),
+            Turn::Back => (negate(forward), up),
+            Turn::Up => (up, negate(forward)),
+            Turn::Down => (negate(up), forward),
+        };
+        Some(Orientation { forward, up })
+    }
+
+    fn step(
+        point: CubeCoordinates,
+        heading: Orientation,
+        steps: i64,
+    ) -> CubeCoordinates {
+        let [dx, dy, dz] = heading.forward;
+        CubeCoordinates {
+            pos_x: point.pos_x + dx * steps,
+            pos_y: point.pos_y + dy * steps,
+            pos_z: point.pos_z + dz * steps,
+        }
+    }
+
+    fn origin_distance(point: CubeCoordinates) -> i64 {
+        point.pos_x.abs() + point.pos_y.abs() + point.pos_z.abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day01;
+
+    fn walk<T: Topology>(input: &str) -> (i64, Option<i64>) {
+        let movements = Day01::parse(input).unwrap();
+        (
+            distance::<T, _>(&movements).unwrap(),
+            first_revisit::<T, _>(&movements).unwrap(),
+        )
+    }
+
+    #[test]
+    fn square() {
+        assert_eq!(walk::<Square>("R5, L5, R5, R3"), (12, None));
+        assert_eq!(walk::<Square>("R8, R4, R4, R8"), (8, Some(4)));
+        assert_eq!(walk::<Square>("F2, B1, R3"), (4, Some(1)));
+
+        let movements = Day01::parse("R2, L3\nF1, U2").unwrap();
+        let err = distance::<Square, _>(&movements).err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 2, column: 5 });
+        assert_eq!(
+            err.to_string(),
+            "Invalid turn on a square grid at line 2, column 5: 'U'"
+        );
+    }
+
+    #[test]
+    fn octagonal() {
+        assert_eq!(walk::<Octagonal>("R2, L2"), (4, None));
+        assert_eq!(walk::<Octagonal>("R2, B1, R1"), (1, Some(1)));
+    }
+
+    #[test]
+    fn hexagonal() {
+        assert_eq!(walk::<Hexagonal>("F3, R3"), (6, None));
+        assert_eq!(walk::<Hexagonal>("R1, R1, R1, R1, R1, R1"), (0, Some(0)));
+    }
+
+    #[test]
+    fn cubic() {
+        assert_eq!(walk::<Cubic>("U3, L2"), (5, None));
+        assert_eq!(walk::<Cubic>("U1, D1, D1, D1, D1"), (1, Some(0)));
+
+        // Without leaving the ground, walks are the same as on the city grid
+        let input = include_str!("../input.txt");
+        assert_eq!(walk::<Cubic>(input), walk::<Square>(input));
+    }
+}
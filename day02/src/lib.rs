@@ -0,0 +1,289 @@
+use aoc::{Error, Solution};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// The keypad of part 1, with keys laid out as a text grid.
+pub const SQUARE_LAYOUT: &str = include_str!("../keypads/square.txt");
+
+/// The keypad of part 2; blank positions are holes in the keypad.
+pub const DIAMOND_LAYOUT: &str = include_str!("../keypads/diamond.txt");
+
+pub const START_KEY: char = '5';
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+}
+
+/// The moves to find one key of the bathroom code.
+#[derive(Debug)]
+pub struct Instruction {
+    moves: Vec<Direction>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+struct Coordinates {
+    row: i32,
+    col: i32,
+}
+
+impl Coordinates {
+    fn step(&self, direction: &Direction) -> Self {
+        let (row, col) = match direction {
+            Direction::Up => (self.row - 1, self.col),
+            Direction::Down => (self.row + 1, self.col),
+            Direction::Left => (self.row, self.col - 1),
+            Direction::Right => (self.row, self.col + 1),
+        };
+        Self { row, col }
+    }
+}
+
+/// A keypad with keys at arbitrary positions of a grid, stored as a graph
+/// where every key leads to a key in each direction; moves that would leave
+/// the keypad lead back to the same key.
+#[derive(Debug)]
+pub struct Keypad {
+    keys: Vec<char>,
+    neighbours: Vec<[usize; 4]>,
+}
+
+impl Keypad {
+    pub fn square() -> Self {
+        SQUARE_LAYOUT.parse().unwrap()
+    }
+
+    pub fn diamond() -> Self {
+        DIAMOND_LAYOUT.parse().unwrap()
+    }
+
+    /// The keys in the order they appear in the layout.
+    pub fn keys(&self) -> &[char] {
+        &self.keys
+    }
+
+    fn index(&self, key: char) -> Option<usize> {
+        self.keys.iter().position(|&ch| ch == key)
+    }
+
+    /// The key reached by moving from `key` in `direction`, or `None` if
+    /// `key` is not on the keypad.
+    pub fn neighbour(&self, key: char, direction: Direction) -> Option<char> {
+        let index = self.index(key)?;
+        Some(self.keys[self.neighbours[index][direction as usize]])
+    }
+
+    /// Returns the keys visited by following each instruction from where
+    /// the previous one ended, starting with the key each instruction starts
+    /// from, or `None` if `start` is not on the keypad.
+    pub fn paths(
+        &self,
+        start: char,
+        instructions: &[Instruction],
+    ) -> Option<Vec<Vec<char>>> {
+        let mut index = self.index(start)?;
+        let paths = instructions
+            .iter()
+            .map(|instruction| {
+                let mut path = vec![self.keys[index]];
+                for &direction in &instruction.moves {
+                    index = self.neighbours[index][direction as usize];
+                    path.push(self.keys[index]);
+                }
+                path
+            })
+            .collect();
+        Some(paths)
+    }
+
+    /// Returns the keys pressed at the end of each instruction, or `None` if
+    /// `start` is not on the keypad.
+    pub fn code(
+        &self,
+        start: char,
+        instructions: &[Instruction],
+    ) -> Option<String> {
+        let paths = self.paths(start, instructions)?;
+        Some(paths.iter().filter_map(|path| path.last()).collect())
+    }
+}
+
+pub fn part1(instructions: &[Instruction]) -> Option<String> {
+    Keypad::square().code(START_KEY, instructions)
+}
+
+pub fn part2(instructions: &[Instruction]) -> Option<String> {
+    Keypad::diamond().code(START_KEY, instructions)
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Instruction>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    const DAY: u32 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                line.trim_end()
+                    .parse()
+                    .map_err(|err: Error| err.at_line(index + 1))
+            })
+            .collect()
+    }
+
+    fn part1(instructions: &Self::Input) -> Result<Option<String>, Error> {
+        Ok(part1(instructions))
+    }
+
+    fn part2(instructions: &Self::Input) -> Result<Option<String>, Error> {
+        Ok(part2(instructions))
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = Error;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        match ch.to_ascii_uppercase() {
+            'U' => Ok(Self::Up),
+            'D' => Ok(Self::Down),
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            _ => Err(Error::format(1, ch, "Invalid direction")),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let moves = s
+            .chars()
+            .enumerate()
+            .map(|(index, ch)| {
+                Direction::try_from(ch).map_err(|err| err.shifted(index))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { moves })
+    }
+}
+
+impl FromStr for Keypad {
+    type Err = Error;
+
+    /// Reads a keypad from a text grid where every character other than
+    /// whitespace is a key.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut positions = HashMap::new();
+        let mut keys = Vec::new();
+        let mut seen = HashMap::new();
+        for (row, line) in s.lines().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                if ch.is_whitespace() {
+                    continue;
+                }
+                let position = (row + 1, col + 1);
+                if let Some((line, column)) = seen.insert(ch, position) {
+                    return Err(Error::value(
+                        col + 1,
+                        ch,
+                        format!(
+                            "Duplicate key, first seen at line {}, column {}",
+                            line, column
+                        ),
+                    )
+                    .at_line(row + 1));
+                }
+                let coordinates = Coordinates {
+                    row: row as i32,
+                    col: col as i32,
+                };
+                positions.insert(coordinates, keys.len());
+                keys.push((coordinates, ch));
+            }
+        }
+        if keys.is_empty() {
+            return Err(Error::format(1, s, "Empty keypad"));
+        }
+
+        let neighbours = keys
+            .iter()
+            .enumerate()
+            .map(|(index, (coordinates, _))| {
+                let mut neighbours = [index; 4];
+                for direction in &Direction::ALL {
+                    let next = coordinates.step(direction);
+                    if let Some(&next_index) = positions.get(&next) {
+                        neighbours[*direction as usize] = next_index;
+                    }
+                }
+                neighbours
+            })
+            .collect();
+        Ok(Self {
+            keys: keys.into_iter().map(|(_, ch)| ch).collect(),
+            neighbours,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "ULL\nRRDDD\nLURDL\nUUUUD\n";
+
+    #[test]
+    fn sample() {
+        let instructions = Day02::parse(SAMPLE).unwrap();
+        assert_eq!(part1(&instructions), Some("1985".to_string()));
+        assert_eq!(part2(&instructions), Some("5DB3".to_string()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Day02::parse("ULL\nRRXDD").err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 2, column: 3 });
+        assert_eq!(err.reason(), "Invalid direction");
+
+        let err = "12\n31".parse::<Keypad>().err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 2, column: 2 });
+        assert_eq!(err.text(), "1");
+    }
+
+    #[test]
+    fn keypad_graph() {
+        let keypad = Keypad::diamond();
+        assert_eq!(keypad.keys().iter().collect::<String>(), "123456789ABCD");
+        assert_eq!(keypad.neighbour('5', Direction::Up), Some('5'));
+        assert_eq!(keypad.neighbour('7', Direction::Down), Some('B'));
+        assert_eq!(keypad.neighbour('E', Direction::Up), None);
+
+        let instructions = Day02::parse("ULL\nRRDDD").unwrap();
+        assert_eq!(
+            Keypad::square().paths('5', &instructions),
+            Some(vec![
+                vec!['5', '2', '1', '1'],
+                vec!['1', '2', '3', '6', '9', '9']
+            ])
+        );
+        let phone: Keypad = "123\n456\n789\n*0#".parse().unwrap();
+        assert_eq!(phone.code('0', &instructions), Some("7#".to_string()));
+    }
+}
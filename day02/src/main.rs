@@ -0,0 +1,145 @@
+use aoc::answers::Answers;
+use aoc::input::read_input;
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, run, stats_arg,
+    Format, Part,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::Solution;
+use clap::{crate_description, value_t_or_exit, App, Arg};
+use day02::{Day02, Instruction, Keypad, START_KEY};
+use std::fs::read_to_string;
+use std::process::exit;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn main() {
+    let args = App::new(crate_description!())
+        .arg(
+            Arg::with_name("INPUT")
+                .help("Sets the input file to use [default: cached input]")
+                .index(1),
+        )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
+        .arg(
+            Arg::with_name("KEYPAD")
+                .long("keypad")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Finds the code on the keypad laid out in FILE, with blanks as holes"),
+        )
+        .arg(
+            Arg::with_name("START")
+                .long("start")
+                .takes_value(true)
+                .value_name("KEY")
+                .help("Sets the key to start from [default: 5]"),
+        )
+        .arg(
+            Arg::with_name("PATH")
+                .long("path")
+                .help("Prints the keys visited by each instruction line"),
+        )
+        .get_matches();
+
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+    let (contents, filename) = read_input(args.value_of("INPUT"), Day02::DAY)
+        .unwrap_or_else(exit_with_error(Day02::DAY, format, 2));
+    let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+        .unwrap_or_else(exit_with_error(Day02::DAY, format, 2));
+
+    if ["KEYPAD", "START", "PATH"]
+        .iter()
+        .any(|&name| args.is_present(name))
+    {
+        let start = match args.value_of("START") {
+            Some(_) => value_t_or_exit!(args.value_of("START"), char),
+            None => START_KEY,
+        };
+        let keypads = match args.value_of("KEYPAD") {
+            Some(filename) => {
+                vec![(filename.to_string(), read_keypad(filename, format))]
+            }
+            None => vec![
+                ("Part 1".to_string(), Keypad::square()),
+                ("Part 2".to_string(), Keypad::diamond()),
+            ],
+        };
+        let instructions = Day02::parse(&contents)
+            .unwrap_or_else(exit_with_error(Day02::DAY, format, 3));
+        for (name, keypad) in &keypads {
+            print_code(
+                name,
+                keypad,
+                start,
+                &instructions,
+                args.is_present("PATH"),
+                format,
+            );
+        }
+        exit(0);
+    }
+
+    let reports =
+        answers.check(run::<Day02>(&contents, &[Part::One, Part::Two]));
+    for report in &reports {
+        report.print(format, stats);
+    }
+    exit(exit_code(&reports));
+}
+
+fn read_keypad(filename: &str, format: Format) -> Keypad {
+    let layout = read_to_string(filename)
+        .map_err(|err| format!("Failed to read file '{}': {}", filename, err))
+        .unwrap_or_else(exit_with_error(Day02::DAY, format, 2));
+    layout
+        .parse()
+        .map_err(|err| format!("Invalid keypad in '{}': {}", filename, err))
+        .unwrap_or_else(exit_with_error(Day02::DAY, format, 2))
+}
+
+fn print_code(
+    name: &str,
+    keypad: &Keypad,
+    start: char,
+    instructions: &[Instruction],
+    print_paths: bool,
+    format: Format,
+) {
+    let paths = keypad
+        .paths(start, instructions)
+        .ok_or_else(|| {
+            format!("{}: key '{}' is not on the keypad", name, start)
+        })
+        .unwrap_or_else(exit_with_error(Day02::DAY, format, 3));
+    let code = paths
+        .iter()
+        .filter_map(|path| path.last())
+        .collect::<String>();
+    if format == Format::Json {
+        let mut record = serde_json::json!({
+            "keypad": name,
+            "start": start,
+            "code": code,
+        });
+        if print_paths {
+            record["paths"] = serde_json::json!(paths);
+        }
+        println!("{}", record);
+        return;
+    }
+    println!("{}: {}", name, code);
+    if print_paths {
+        for (index, path) in paths.iter().enumerate() {
+            let keys = path.iter().map(char::to_string).collect::<Vec<_>>();
+            println!("  {}: {}", index + 1, keys.join(" "));
+        }
+    }
+}
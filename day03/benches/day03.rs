@@ -0,0 +1,25 @@
+use aoc::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day03::{stream, Day03};
+
+const INPUT: &str = include_str!("../input.txt");
+
+fn parts(c: &mut Criterion) {
+    let input = Day03::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day03");
+    group
+        .bench_function("parse", |b| b.iter(|| Day03::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day03::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day03::part2(black_box(&input)))
+    });
+    group.bench_function("stream", |b| {
+        b.iter(|| stream::count::<u32, _>(black_box(INPUT.as_bytes())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parts);
+criterion_main!(benches);
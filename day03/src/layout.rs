@@ -0,0 +1,256 @@
+//! Ways of reading polygons from rows of side lengths. Polygons that would
+//! need values missing from the input are left out, and the rows that are
+//! too short or too long for the layout are reported instead.
+
+use crate::side::Side;
+use aoc::Error;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// How to read polygons with a given number of sides from the input.
+/// Layouts with fewer than three sides read no polygons.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Layout {
+    /// Each row is a polygon.
+    Rows(usize),
+    /// Every block of as many rows as sides gives one polygon per column,
+    /// reading as many columns as sides.
+    Columns(usize),
+    /// Every block of as many rows as sides, with as many values as sides
+    /// in each row, gives one polygon per diagonal, wrapping around the
+    /// block: polygon `i` takes the value in column `i + j` of row `j`.
+    Diagonals(usize),
+}
+
+/// The polygons read with a layout, along with the 1-based line numbers of
+/// the rows that did not fit it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Grouping<T> {
+    pub polygons: Vec<Vec<T>>,
+    /// Rows with a different number of values than the layout needs, so
+    /// that some of their values were not read
+    pub ragged: Vec<usize>,
+    /// Rows at the end of the input too few to fill a block
+    pub leftover: Vec<usize>,
+}
+
+impl Layout {
+    /// The number of sides of each polygon.
+    pub fn sides(&self) -> usize {
+        match *self {
+            Self::Rows(sides)
+            | Self::Columns(sides)
+            | Self::Diagonals(sides) => sides,
+        }
+    }
+
+    /// How many rows each block has: one for `Rows`, as many as sides
+    /// otherwise, but at least one.
+    pub fn block_len(&self) -> usize {
+        match *self {
+            Self::Rows(_) => 1,
+            Self::Columns(sides) | Self::Diagonals(sides) => sides.max(1),
+        }
+    }
+
+    /// The polygons of one block of rows, each with its index in the block.
+    /// Polygons that need values missing from the block are left out, and
+    /// layouts with fewer than three sides read none.
+    pub fn block_polygons<T, R>(&self, block: &[R]) -> Vec<(usize, Vec<T>)>
+    where
+        T: Copy,
+        R: AsRef<[T]>,
+    {
+        let sides = self.sides();
+        if sides < 3 {
+            return Vec::new();
+        }
+        if let Self::Rows(_) = self {
+            let row = block[0].as_ref();
+            return if row.len() == sides {
+                vec![(0, row.to_vec())]
+            } else {
+                Vec::new()
+            };
+        }
+        (0..sides)
+            .filter_map(|polygon| {
+                block
+                    .iter()
+                    .enumerate()
+                    .map(|(row_index, row)| {
+                        let column = match self {
+                            Self::Diagonals(_) => (polygon + row_index) % sides,
+                            _ => polygon,
+                        };
+                        row.as_ref().get(column).copied()
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map(|values| (polygon, values))
+            })
+            .collect()
+    }
+
+    pub fn group<T: Copy>(&self, numbers: &[Vec<T>]) -> Grouping<T> {
+        let blocks = numbers.chunks_exact(self.block_len());
+        let full = numbers.len() - blocks.remainder().len();
+        let mut polygons = blocks
+            .flat_map(|block| self.block_polygons(block))
+            .collect::<Vec<_>>();
+        if let Self::Columns(_) = self {
+            // Read down each column through every block before the next
+            polygons.sort_by_key(|(column, _)| *column);
+        }
+        Grouping {
+            polygons: polygons.into_iter().map(|(_, values)| values).collect(),
+            ragged: (1..=full)
+                .filter(|&line| numbers[line - 1].len() != self.sides())
+                .collect(),
+            leftover: (full + 1..=numbers.len()).collect(),
+        }
+    }
+}
+
+/// Whether the sides can be the sides of a polygon with some area, which
+/// needs at least three sides and each one shorter than all others
+/// together.
+pub fn is_polygon<T: Side>(sides: &[T]) -> bool {
+    if sides.len() < 3 {
+        return false;
+    }
+    let mut sides = sides.to_vec();
+    sides.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let (&longest, others) = sides.split_last().unwrap();
+    T::cmp_sum(others, longest) == Ordering::Greater
+}
+
+impl FromStr for Layout {
+    type Err = Error;
+
+    /// Reads layouts such as `rows`, `columns:4` or `diagonals:5`; layouts
+    /// without a number of sides are of triangles.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, sides) = match s.find(':') {
+            Some(index) => {
+                let sides = s[index + 1..]
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&sides| sides >= 3)
+                    .ok_or_else(|| {
+                        Error::value(
+                            index + 2,
+                            &s[index + 1..],
+                            "Invalid number of sides",
+                        )
+                    })?;
+                (&s[..index], sides)
+            }
+            None => (s, 3),
+        };
+        match name {
+            "rows" => Ok(Self::Rows(sides)),
+            "columns" => Ok(Self::Columns(sides)),
+            "diagonals" => Ok(Self::Diagonals(sides)),
+            _ => Err(Error::format(1, name, "Unknown layout")),
+        }
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Rows(_) => "rows",
+            Self::Columns(_) => "columns",
+            Self::Diagonals(_) => "diagonals",
+        };
+        write!(f, "{}:{}", name, self.sides())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day03;
+    use aoc::Solution;
+
+    #[test]
+    fn layouts() {
+        let numbers =
+            Day03::parse("1 2 3\n4 5 6\n7 8\n10 11 12\n13 14 15").unwrap();
+
+        let rows = Layout::Rows(3).group(&numbers);
+        assert_eq!(rows.polygons.len(), 4);
+        assert_eq!((rows.ragged, rows.leftover), (vec![3], vec![]));
+
+        let columns = Layout::Columns(3).group(&numbers);
+        assert_eq!(columns.polygons, vec![vec![1, 4, 7], vec![2, 5, 8]]);
+        assert_eq!((columns.ragged, columns.leftover), (vec![3], vec![4, 5]));
+
+        let diagonals = Layout::Diagonals(3).group(&numbers);
+        assert_eq!(diagonals.polygons, vec![vec![2, 6, 7], vec![3, 4, 8]]);
+
+        let squares = Layout::Columns(4).group(&numbers);
+        assert_eq!(squares.polygons[0], vec![1, 4, 7, 10]);
+        assert_eq!(squares.leftover, vec![5]);
+
+        // Values past the number of sides are not read, even if every row
+        // of a block has them
+        let wide = Day03::parse("3 4 5 6\n3 4 5 6\n3 4 5 6\n1 2 3\n4 5\n6 7 8")
+            .unwrap();
+        let columns = Layout::Columns(3).group(&wide);
+        assert_eq!(
+            columns.polygons,
+            vec![
+                vec![3, 3, 3],
+                vec![1, 4, 6],
+                vec![4, 4, 4],
+                vec![2, 5, 7],
+                vec![5, 5, 5]
+            ]
+        );
+        assert_eq!(columns.ragged, vec![1, 2, 3, 5]);
+        assert_eq!(crate::part2(&wide), 3);
+        let squares = Layout::Columns(4).group(&vec![wide[0].clone(); 4]);
+        assert_eq!(squares.polygons.len(), 4);
+        assert!(squares.ragged.is_empty());
+    }
+
+    #[test]
+    fn too_few_sides() {
+        let numbers = Day03::parse("1 2\n\n3").unwrap();
+        for layout in &[
+            Layout::Rows(0),
+            Layout::Rows(2),
+            Layout::Columns(0),
+            Layout::Columns(2),
+            Layout::Diagonals(0),
+            Layout::Diagonals(1),
+        ] {
+            let grouping = layout.group(&numbers);
+            assert!(grouping.polygons.is_empty(), "{:?}", layout);
+        }
+        assert_eq!(Layout::Columns(0).group(&numbers).ragged, vec![1, 3]);
+    }
+
+    #[test]
+    fn polygons() {
+        assert!(is_polygon(&[1_u32, 1, 1, 2]));
+        assert!(!is_polygon(&[1_u32, 1, 1, 3]));
+        assert!(!is_polygon(&[5_u32, 5]));
+        assert!(is_polygon(&[3_u32, 5, 4]));
+        assert!(!is_polygon(&[3_u32, 5, 2]));
+
+        assert_eq!("columns:4".parse(), Ok(Layout::Columns(4)));
+        assert_eq!("rows".parse(), Ok(Layout::Rows(3)));
+        let err = "diagonals:2".parse::<Layout>().err().unwrap();
+        assert_eq!(
+            err.position(),
+            aoc::Position {
+                line: 1,
+                column: 11
+            }
+        );
+        assert!("spiral".parse::<Layout>().is_err());
+    }
+}
@@ -1,30 +1,327 @@
-fn is_triangle(x: u32, y: u32, z: u32) -> bool {
-    x + y > z && x + z > y && y + z > x
+pub mod layout;
+pub mod side;
+pub mod stream;
+
+use aoc::{words, Error, Solution};
+use layout::Layout;
+use side::Side;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+
+/// Three side lengths, kept in ascending order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Triangle<T = u32> {
+    sides: [T; 3],
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SideKind {
+    Scalene,
+    Isosceles,
+    Equilateral,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AngleKind {
+    Acute,
+    Right,
+    Obtuse,
+}
+
+impl<T: Side> Triangle<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        let mut sides = [x, y, z];
+        sides.sort_unstable_by(|a, b| {
+            a.partial_cmp(b).unwrap_or(Ordering::Equal)
+        });
+        Self { sides }
+    }
+
+    pub fn sides(&self) -> [T; 3] {
+        self.sides
+    }
+
+    /// Whether the sides enclose no area: the longest side is at least as
+    /// long as the other two together, so they can't form a triangle.
+    pub fn is_degenerate(&self) -> bool {
+        let [a, b, c] = self.sides;
+        T::cmp_sum(&[a, b], c) != Ordering::Greater
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.is_degenerate()
+    }
+
+    /// How many sides are equal, or `None` for degenerate triangles.
+    pub fn side_kind(&self) -> Option<SideKind> {
+        let [a, b, c] = self.sides;
+        if self.is_degenerate() {
+            None
+        } else if T::same_length(a, c, c) {
+            Some(SideKind::Equilateral)
+        } else if T::same_length(a, b, c) || T::same_length(b, c, c) {
+            Some(SideKind::Isosceles)
+        } else {
+            Some(SideKind::Scalene)
+        }
+    }
+
+    /// The kind of the largest angle, or `None` for degenerate triangles.
+    pub fn angle_kind(&self) -> Option<AngleKind> {
+        if self.is_degenerate() {
+            return None;
+        }
+        let [a, b, c] = self.sides;
+        Some(match T::cmp_squares(a, b, c) {
+            Ordering::Greater => AngleKind::Acute,
+            Ordering::Equal => AngleKind::Right,
+            Ordering::Less => AngleKind::Obtuse,
+        })
+    }
+
+    /// The sum of the sides, or `None` if it is too large for `T`.
+    pub fn perimeter(&self) -> Option<T> {
+        let [a, b, c] = self.sides;
+        a.checked_add(b)?.checked_add(c)
+    }
+
+    /// The area by Heron's formula, in the form that stays accurate for
+    /// needle-like triangles; degenerate triangles have no area.
+    pub fn area(&self) -> f64 {
+        if self.is_degenerate() {
+            return 0.0;
+        }
+        // Sides are scaled down to at most 1 so that the product of the
+        // factors doesn't overflow
+        let scale = self.sides[2].to_f64();
+        let [c, b, a] = self.sides.map(|side| side.to_f64() / scale);
+        let product =
+            (a + (b + c)) * (c - (a - b)) * (c + (a - b)) * (a + (b - c));
+        product.sqrt() / 4.0 * scale * scale
+    }
+}
+
+/// The triangles read with a layout of three-sided polygons.
+fn triangles<T: Side>(
+    layout: Layout,
+    numbers: &[Vec<T>],
+) -> impl Iterator<Item = Triangle<T>> {
+    layout
+        .group(numbers)
+        .polygons
+        .into_iter()
+        .map(|sides| Triangle::new(sides[0], sides[1], sides[2]))
+}
+
+/// The side lengths of each row with exactly three of them.
+pub fn rows<T: Side>(numbers: &[Vec<T>]) -> impl Iterator<Item = Triangle<T>> {
+    triangles(Layout::Rows(3), numbers)
 }
 
-pub fn part1(numbers: &[Vec<u32>]) -> usize {
-    numbers
+/// The side lengths read down each column in blocks of three rows; blocks
+/// left incomplete at the end are left out.
+pub fn columns<T: Side>(
+    numbers: &[Vec<T>],
+) -> impl Iterator<Item = Triangle<T>> {
+    triangles(Layout::Columns(3), numbers)
+}
+
+/// How many polygons read with `layout` have some area.
+pub fn count_polygons<T: Side>(layout: Layout, numbers: &[Vec<T>]) -> usize {
+    layout
+        .group(numbers)
+        .polygons
         .iter()
-        .filter(|row| row.len() == 3 && is_triangle(row[0], row[1], row[2]))
+        .filter(|sides| layout::is_polygon(sides))
         .count()
 }
 
-pub fn part2(numbers: &[Vec<u32>]) -> usize {
-    (0..3)
-        .map(|col| {
-            numbers
-                .chunks_exact(3)
-                .filter_map(|chunk| {
-                    let x = chunk[0].get(col)?;
-                    let y = chunk[1].get(col)?;
-                    let z = chunk[2].get(col)?;
-                    if is_triangle(*x, *y, *z) {
-                        Some(())
-                    } else {
-                        None
-                    }
-                })
-                .count()
-        })
-        .sum()
+pub fn part1<T: Side>(numbers: &[Vec<T>]) -> usize {
+    count_polygons(Layout::Rows(3), numbers)
+}
+
+pub fn part2<T: Side>(numbers: &[Vec<T>]) -> usize {
+    count_polygons(Layout::Columns(3), numbers)
+}
+
+/// How many triangles fall in each category; every triangle is either
+/// degenerate or has both a side kind and an angle kind.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Histogram {
+    pub triangles: usize,
+    pub degenerate: usize,
+    pub scalene: usize,
+    pub isosceles: usize,
+    pub equilateral: usize,
+    pub acute: usize,
+    pub right: usize,
+    pub obtuse: usize,
+}
+
+impl Histogram {
+    pub fn new<T, I>(triangles: I) -> Self
+    where
+        T: Side,
+        I: IntoIterator<Item = Triangle<T>>,
+    {
+        let mut histogram = Self::default();
+        for triangle in triangles {
+            histogram.triangles += 1;
+            match triangle.side_kind() {
+                Some(SideKind::Scalene) => histogram.scalene += 1,
+                Some(SideKind::Isosceles) => histogram.isosceles += 1,
+                Some(SideKind::Equilateral) => histogram.equilateral += 1,
+                None => histogram.degenerate += 1,
+            }
+            match triangle.angle_kind() {
+                Some(AngleKind::Acute) => histogram.acute += 1,
+                Some(AngleKind::Right) => histogram.right += 1,
+                Some(AngleKind::Obtuse) => histogram.obtuse += 1,
+                None => {}
+            }
+        }
+        histogram
+    }
+
+    /// Each category with its number of triangles.
+    pub fn categories(&self) -> [(&'static str, usize); 7] {
+        [
+            ("degenerate", self.degenerate),
+            ("scalene", self.scalene),
+            ("isosceles", self.isosceles),
+            ("equilateral", self.equilateral),
+            ("acute", self.acute),
+            ("right", self.right),
+            ("obtuse", self.obtuse),
+        ]
+    }
+}
+
+/// Width of the longest bar of a histogram.
+const BAR_WIDTH: usize = 40;
+
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (name, count) in &self.categories() {
+            let width = (count * BAR_WIDTH).div_ceil(self.triangles.max(1));
+            let line =
+                format!("  {:<11} {:>6} {}", name, count, "#".repeat(width));
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// The puzzle with sides of type `T`, such as `u128` for inputs with sides
+/// too long for `u32` or `f64` for fractional sides.
+pub struct Triangles<T>(PhantomData<T>);
+
+pub type Day03 = Triangles<u32>;
+
+impl<T> Solution for Triangles<T>
+where
+    T: Side,
+    T::Err: Display,
+{
+    type Input = Vec<Vec<T>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const DAY: u32 = 3;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                words(line)
+                    .map(|(column, word)| {
+                        side::parse(word).map_err(|reason| {
+                            Error::value(column, word, reason)
+                                .at_line(index + 1)
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn part1(numbers: &Self::Input) -> Result<Option<usize>, Error> {
+        Ok(Some(part1(numbers)))
+    }
+
+    fn part2(numbers: &Self::Input) -> Result<Option<usize>, Error> {
+        Ok(Some(part2(numbers)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classification() {
+        let right = Triangle::new(5_u32, 3, 4);
+        assert_eq!(right.sides(), [3, 4, 5]);
+        assert_eq!(right.side_kind(), Some(SideKind::Scalene));
+        assert_eq!(right.angle_kind(), Some(AngleKind::Right));
+        assert_eq!((right.perimeter(), right.area()), (Some(12), 6.0));
+
+        let flat = Triangle::new(5_u32, 10, 25);
+        assert!(flat.is_degenerate());
+        assert_eq!((flat.side_kind(), flat.angle_kind()), (None, None));
+        assert!(Triangle::new(1_u32, 2, 3).is_degenerate());
+
+        let equilateral = Triangle::new(2_u32, 2, 2);
+        assert_eq!(equilateral.side_kind(), Some(SideKind::Equilateral));
+        assert_eq!(equilateral.angle_kind(), Some(AngleKind::Acute));
+        assert!((equilateral.area() - 3_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(
+            Triangle::new(2_u32, 2, 3).angle_kind(),
+            Some(AngleKind::Obtuse)
+        );
+
+        let max = u32::MAX;
+        assert!(Triangle::new(max, max, max).is_valid());
+        assert_eq!(Triangle::new(max, max, max).perimeter(), None);
+        let wide = Triangle::new(3_u128 << 100, 4 << 100, 5 << 100);
+        assert_eq!(wide.angle_kind(), Some(AngleKind::Right));
+
+        let right = Triangle::new(0.3, 0.4, 0.5);
+        assert_eq!(right.angle_kind(), Some(AngleKind::Right));
+        assert!((right.area() - 0.06).abs() < 1e-12);
+        assert!(Triangle::new(0.1, 0.2, 0.3).is_degenerate());
+    }
+
+    #[test]
+    fn wide_sides() {
+        let input = "5000000000 5000000000 5000000000\n1 1 3";
+        assert!(Day03::parse(input).is_err());
+        let numbers = Triangles::<u64>::parse(input).unwrap();
+        assert_eq!(part1(&numbers), 1);
+        let err = Triangles::<f64>::parse("1.5 2 inf").err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 1, column: 7 });
+        assert_eq!(err.reason(), "Invalid side length");
+    }
+
+    #[test]
+    fn reading_orders() {
+        let numbers =
+            Day03::parse("101 301 501\n102 302 502\n103 303 503\n1 2").unwrap();
+        assert_eq!(rows(&numbers).count(), 3);
+        assert_eq!(
+            columns(&numbers).next(),
+            Some(Triangle::new(101, 102, 103))
+        );
+
+        let histogram = Histogram::new(rows(&numbers));
+        assert_eq!(histogram.triangles, 3);
+        assert_eq!(histogram.degenerate, 3);
+        let histogram = Histogram::new(columns(&numbers));
+        assert_eq!(
+            (histogram.scalene, histogram.acute, histogram.degenerate),
+            (3, 3, 0)
+        );
+    }
 }
@@ -1,58 +1,254 @@
-use clap::{crate_description, App, Arg};
-use day03::{part1, part2};
+use aoc::answers::Answers;
+use aoc::input::{read_input, InputCache, YEAR};
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, run, stats_arg,
+    Format, Part, Report,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::{Error, Solution};
+use clap::{crate_description, value_t_or_exit, values_t, App, Arg};
+use day03::layout::{Grouping, Layout};
+use day03::side::Side;
+use day03::{columns, rows, stream, Day03, Histogram, Triangles};
+use std::fmt::Display;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Error, ErrorKind};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
 fn main() {
     let args = App::new(crate_description!())
         .arg(
             Arg::with_name("INPUT")
-                .help("Sets the input file to use")
-                .required(true)
+                .help("Sets the input file to use [default: cached input]")
                 .index(1),
         )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
+        .arg(
+            Arg::with_name("REPORT")
+                .long("report")
+                .help("Prints how many triangles are of each kind"),
+        )
+        .arg(
+            Arg::with_name("SIDES")
+                .long("sides")
+                .takes_value(true)
+                .possible_values(&["u32", "u64", "u128", "f32", "f64"])
+                .default_value("u32")
+                .help("Sets the type of side lengths"),
+        )
+        .arg(
+            Arg::with_name("LAYOUT")
+                .long("layout")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Counts polygons read as rows, columns or diagonals"),
+        )
+        .arg(
+            Arg::with_name("STREAM")
+                .long("stream")
+                .conflicts_with_all(&["REPORT", "LAYOUT"])
+                .help("Solves both parts in one pass without reading it all"),
+        )
         .get_matches();
 
-    println!(crate_description!());
-    let numbers = read_input(args.value_of("INPUT").unwrap());
-    println!("Part 1: {}", part1(&numbers));
-    println!("Part 2: {}", part2(&numbers));
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+    let (contents, filename) = if args.is_present("STREAM") {
+        match args.value_of("INPUT") {
+            Some(filename) => Ok(PathBuf::from(filename)),
+            None => InputCache::from_env().resolve(YEAR, Day03::DAY),
+        }
+        .map(|filename| (None, filename))
+    } else {
+        read_input(args.value_of("INPUT"), Day03::DAY)
+            .map(|(contents, filename)| (Some(contents), filename))
+    }
+    .unwrap_or_else(exit_with_error(Day03::DAY, format, 2));
+    let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+        .unwrap_or_else(exit_with_error(Day03::DAY, format, 2));
+
+    let mode = if let Some(contents) = &contents {
+        if args.is_present("LAYOUT") {
+            let layouts = values_t!(args.values_of("LAYOUT"), Layout)
+                .unwrap_or_else(|err| err.exit());
+            Mode::Layouts(contents, layouts)
+        } else if args.is_present("REPORT") {
+            Mode::Report(contents)
+        } else {
+            Mode::Solve(contents)
+        }
+    } else {
+        Mode::Stream(&filename)
+    };
+    let reports = match args.value_of("SIDES") {
+        Some("u64") => solve::<u64>(&mode, format),
+        Some("u128") => solve::<u128>(&mode, format),
+        Some("f32") => solve::<f32>(&mode, format),
+        Some("f64") => solve::<f64>(&mode, format),
+        _ => solve::<u32>(&mode, format),
+    };
+    let reports = answers.check(reports);
+    for report in &reports {
+        report.print(format, stats);
+    }
+    exit(exit_code(&reports));
+}
+
+/// What to do with the input, which is only read all at once when not
+/// streaming it.
+enum Mode<'a> {
+    Solve(&'a str),
+    Stream(&'a Path),
+    Report(&'a str),
+    Layouts(&'a str, Vec<Layout>),
 }
 
-fn read_input(filename: &str) -> Vec<Vec<u32>> {
-    let file = match File::open(filename) {
-        Ok(file) => file,
-        Err(err) => {
-            println!("Failed to open file '{}': {}", filename, err.to_string());
-            exit(2);
+/// Solves both parts with sides of type `T`, or prints a report and exits
+/// in the modes that report on the input.
+fn solve<T>(mode: &Mode, format: Format) -> Vec<Report>
+where
+    T: Side,
+    T::Err: Display,
+{
+    let contents = match *mode {
+        Mode::Solve(contents) => {
+            return run::<Triangles<T>>(contents, &[Part::One, Part::Two]);
         }
+        Mode::Stream(filename) => return stream_parts::<T>(filename, format),
+        Mode::Report(contents) | Mode::Layouts(contents, _) => contents,
     };
+    let numbers = Triangles::<T>::parse(contents)
+        .unwrap_or_else(exit_with_error(Day03::DAY, format, 3));
+    match mode {
+        Mode::Layouts(_, layouts) => {
+            for layout in layouts {
+                print_grouping(layout, &layout.group(&numbers), format);
+            }
+        }
+        _ => print_report(
+            &Histogram::new(rows(&numbers)),
+            &Histogram::new(columns(&numbers)),
+            format,
+        ),
+    }
+    exit(0);
+}
 
-    match BufReader::new(file)
-        .lines()
-        .map(|line| {
-            line.and_then(|value| {
-                value
-                    .split_whitespace()
-                    .map(|s| {
-                        s.parse().map_err(|err| {
-                            Error::new(ErrorKind::InvalidData, err)
-                        })
-                    })
-                    .collect::<Result<Vec<_>, _>>()
+/// Most line numbers listed for rows that don't fit a layout.
+const MAX_LINES: usize = 10;
+
+fn print_grouping<T: Side>(
+    layout: &Layout,
+    grouping: &Grouping<T>,
+    format: Format,
+) {
+    let valid = grouping
+        .polygons
+        .iter()
+        .filter(|sides| day03::layout::is_polygon(sides))
+        .count();
+    if format == Format::Json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "layout": layout.to_string(),
+                "polygons": grouping.polygons.len(),
+                "valid": valid,
+                "ragged": grouping.ragged,
+                "leftover": grouping.leftover,
             })
+        );
+        return;
+    }
+    println!(
+        "Layout {}: {} valid of {} polygons",
+        layout,
+        valid,
+        grouping.polygons.len()
+    );
+    for (name, lines) in &[
+        ("Ragged", &grouping.ragged),
+        ("Leftover", &grouping.leftover),
+    ] {
+        if lines.is_empty() {
+            continue;
+        }
+        let mut list = lines
+            .iter()
+            .take(MAX_LINES)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if lines.len() > MAX_LINES {
+            list.push_str(&format!(" and {} more", lines.len() - MAX_LINES));
+        }
+        println!("  {} rows: {}", name, list);
+    }
+}
+
+/// Solves both parts in a single pass over the file, which is timed as
+/// part 1.
+fn stream_parts<T>(filename: &Path, format: Format) -> Vec<Report>
+where
+    T: Side,
+    T::Err: Display,
+{
+    let file = File::open(filename)
+        .map_err(|err| {
+            format!("Failed to read file '{}': {}", filename.display(), err)
         })
-        .collect::<Result<Vec<_>, _>>()
-    {
-        Ok(numbers) => numbers,
-        Err(err) => {
-            println!(
-                "Failed to parse input file '{}': {}",
-                filename,
-                err.to_string()
-            );
-            exit(3);
+        .unwrap_or_else(exit_with_error(Day03::DAY, format, 2));
+    let mut streamed = None;
+    let part1 = Report::time(Day03::DAY, Part::One, || {
+        let counts =
+            stream::count::<T, _>(BufReader::new(file)).map_err(Error::from);
+        streamed = Some(counts.clone());
+        counts.map(|counts| Some(counts.part1))
+    });
+    let part2 = match streamed.unwrap() {
+        Ok(counts) => {
+            if format == Format::Text && counts.ragged + counts.leftover > 0 {
+                println!(
+                    "{} ragged rows, {} leftover rows",
+                    counts.ragged, counts.leftover
+                );
+            }
+            Report::time(Day03::DAY, Part::Two, || Ok(Some(counts.part2)))
         }
+        // Part 2 is counted in the same pass, so it fails the same way
+        Err(err) => Report::failed(Day03::DAY, Part::Two, err),
+    };
+    vec![part1, part2]
+}
+
+fn print_report(rows: &Histogram, columns: &Histogram, format: Format) {
+    if format == Format::Json {
+        let json = |histogram: &Histogram| {
+            let mut map = serde_json::Map::new();
+            map.insert("triangles".to_string(), histogram.triangles.into());
+            for (name, count) in &histogram.categories() {
+                map.insert(name.to_string(), (*count).into());
+            }
+            map
+        };
+        println!(
+            "{}",
+            serde_json::json!({ "rows": json(rows), "columns": json(columns) })
+        );
+        return;
     }
+    println!("Rows: {} triangles", rows.triangles);
+    print!("{}", rows);
+    println!("Columns: {} triangles", columns.triangles);
+    print!("{}", columns);
 }
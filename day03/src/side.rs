@@ -0,0 +1,205 @@
+//! Side lengths of any unsigned integer width or floating-point type.
+//! Comparisons of sums and squares of sides never overflow, so that very
+//! long sides are classified as exactly as short ones.
+
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+/// How far apart floating-point values may be and still count as equal,
+/// relative to the longest side.
+pub const TOLERANCE: f64 = 1e-9;
+
+pub trait Side: Copy + Debug + PartialOrd + FromStr {
+    /// Compares the sum of `sides` with `longest`, which is at least as
+    /// long as each of them.
+    fn cmp_sum(sides: &[Self], longest: Self) -> Ordering;
+
+    /// Compares `a² + b²` with `c²`, for sides with `a <= b <= c`.
+    fn cmp_squares(a: Self, b: Self, c: Self) -> Ordering;
+
+    /// Whether `a` and `b` are the same length in a triangle whose longest
+    /// side is `longest`.
+    fn same_length(a: Self, b: Self, longest: Self) -> bool;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn to_f64(self) -> f64;
+
+    /// Whether the value can be the length of a side.
+    fn is_length(self) -> bool {
+        true
+    }
+}
+
+/// The square of `x` as the high and low halves of a 256-bit number.
+fn wide_square(x: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (high, low) = (x >> 64, x & MASK);
+    let cross = high * low;
+    let middle = ((low * low) >> 64) + 2 * (cross & MASK);
+    (
+        high * high + 2 * (cross >> 64) + (middle >> 64),
+        ((low * low) & MASK) | (middle << 64),
+    )
+}
+
+fn cmp_wide_squares(a: u128, b: u128, c: u128) -> Ordering {
+    let ((a_high, a_low), (b_high, b_low)) = (wide_square(a), wide_square(b));
+    let (low, carry) = a_low.overflowing_add(b_low);
+    let (high, overflow) = a_high.overflowing_add(b_high + u128::from(carry));
+    if overflow {
+        return Ordering::Greater;
+    }
+    let (c_high, c_low) = wide_square(c);
+    (high, low).cmp(&(c_high, c_low))
+}
+
+macro_rules! unsigned_side {
+    ($($int:ty),*) => {$(
+        impl Side for $int {
+            fn cmp_sum(sides: &[Self], longest: Self) -> Ordering {
+                // Takes each side away from the longest instead of adding
+                // them up, which could overflow
+                let mut remaining = longest;
+                for &side in sides {
+                    if side > remaining {
+                        return Ordering::Greater;
+                    }
+                    remaining -= side;
+                }
+                if remaining == 0 {
+                    Ordering::Equal
+                } else {
+                    Ordering::Less
+                }
+            }
+
+            fn cmp_squares(a: Self, b: Self, c: Self) -> Ordering {
+                cmp_wide_squares(a as u128, b as u128, c as u128)
+            }
+
+            fn same_length(a: Self, b: Self, _: Self) -> bool {
+                a == b
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$int>::checked_add(self, other)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+unsigned_side!(u32, u64, u128);
+
+/// Compares `x` with `y` where both are fractions of the longest side.
+fn cmp_fractions(x: f64, y: f64) -> Ordering {
+    if (x - y).abs() <= TOLERANCE {
+        Ordering::Equal
+    } else if x < y {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+macro_rules! float_side {
+    ($($float:ty),*) => {$(
+        impl Side for $float {
+            // Sides are divided by the longest side, so that neither sums
+            // nor squares can overflow and the tolerance is relative
+            fn cmp_sum(sides: &[Self], longest: Self) -> Ordering {
+                if longest == 0.0 {
+                    return Ordering::Equal;
+                }
+                let longest = f64::from(longest);
+                let sum = sides
+                    .iter()
+                    .map(|&side| f64::from(side) / longest)
+                    .sum();
+                cmp_fractions(sum, 1.0)
+            }
+
+            fn cmp_squares(a: Self, b: Self, c: Self) -> Ordering {
+                if c == 0.0 {
+                    return Ordering::Equal;
+                }
+                let c = f64::from(c);
+                let (a, b) = (f64::from(a) / c, f64::from(b) / c);
+                cmp_fractions(a * a + b * b, 1.0)
+            }
+
+            fn same_length(a: Self, b: Self, longest: Self) -> bool {
+                let difference = f64::from(a) - f64::from(b);
+                difference.abs() <= TOLERANCE * f64::from(longest)
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                Some(self + other).filter(|sum| sum.is_finite())
+            }
+
+            fn to_f64(self) -> f64 {
+                f64::from(self)
+            }
+
+            fn is_length(self) -> bool {
+                self.is_finite() && self >= 0.0
+            }
+        }
+    )*};
+}
+
+float_side!(f32, f64);
+
+/// Parses a side length, with the reason it is not one on failure.
+pub fn parse<T>(word: &str) -> Result<T, String>
+where
+    T: Side,
+    T::Err: Display,
+{
+    let side = word.parse::<T>().map_err(|err| err.to_string())?;
+    if side.is_length() {
+        Ok(side)
+    } else {
+        Err("Invalid side length".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_overflow() {
+        let max = u32::MAX;
+        assert_eq!(u32::cmp_sum(&[max, max], max), Ordering::Greater);
+        assert_eq!(u32::cmp_sum(&[1, max - 1], max), Ordering::Equal);
+
+        let max = u128::MAX;
+        assert_eq!(u128::cmp_sum(&[max - 1, max], max), Ordering::Greater);
+        assert_eq!(u128::cmp_squares(max, max, max), Ordering::Greater);
+        assert_eq!(u128::cmp_squares(1, max - 1, max), Ordering::Less);
+        // 3, 4, 5 scaled by 2^124
+        let [a, b, c] = [3_u128 << 124, 4 << 124, 5 << 124];
+        assert_eq!(u128::cmp_squares(a, b, c), Ordering::Equal);
+        assert_eq!(u128::cmp_squares(a, b, c - 1), Ordering::Greater);
+    }
+
+    #[test]
+    fn floats_with_tolerance() {
+        let third = 1.0 / 3.0;
+        assert_eq!(f64::cmp_sum(&[third, 2.0 * third], 1.0), Ordering::Equal);
+        assert_eq!(f64::cmp_squares(0.6, 0.8, 1.0), Ordering::Equal);
+        assert_eq!(
+            f64::cmp_sum(&[f64::MAX, f64::MAX], f64::MAX),
+            Ordering::Greater
+        );
+        assert!(parse::<f64>("NaN").is_err());
+        assert!(parse::<f64>("-1.5").is_err());
+        assert!(parse::<u64>("-1").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Counts the triangles of both parts in a single pass over any reader,
+//! keeping only the current line and the last block of three rows in
+//! memory, so that inputs far larger than memory can be checked.
+
+use crate::layout::{self, Layout};
+use crate::side::{self, Side};
+use aoc::{words, Error};
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead};
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    Value(Error),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Failed to read input: {}", err),
+            Self::Value(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    /// Read failures are not found at any value, so they are placed at the
+    /// start of the input.
+    fn from(err: ParseError) -> Self {
+        match err {
+            ParseError::Io(_) => Error::format(1, "", &err),
+            ParseError::Value(err) => err,
+        }
+    }
+}
+
+/// The answers of both parts and the rows that did not fit them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Counts {
+    pub rows: usize,
+    /// Triangles read by rows
+    pub part1: usize,
+    /// Triangles read down the columns of blocks of three rows
+    pub part2: usize,
+    /// Rows without exactly three values
+    pub ragged: usize,
+    /// Rows at the end of the input too few to fill a block
+    pub leftover: usize,
+}
+
+/// How many polygons of a block read with `layout` have some area.
+fn count_block<T: Side>(layout: Layout, block: &[Vec<T>]) -> usize {
+    layout
+        .block_polygons(block)
+        .iter()
+        .filter(|(_, sides)| layout::is_polygon(sides))
+        .count()
+}
+
+/// Counts the triangles in the rows read from `reader`, grouped the same
+/// way as [`part1`](crate::part1) and [`part2`](crate::part2) group them.
+pub fn count<T, R>(mut reader: R) -> Result<Counts, ParseError>
+where
+    T: Side,
+    T::Err: Display,
+    R: BufRead,
+{
+    let (by_rows, by_columns) = (Layout::Rows(3), Layout::Columns(3));
+    let mut counts = Counts::default();
+    let mut line = String::new();
+    let mut block = vec![Vec::new(); by_columns.block_len()];
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(ParseError::Io)? == 0 {
+            break;
+        }
+        let row = &mut block[counts.rows % by_columns.block_len()];
+        row.clear();
+        for (column, word) in words(&line) {
+            let side = side::parse::<T>(word).map_err(|reason| {
+                let error = Error::value(column, word, reason);
+                ParseError::Value(error.at_line(counts.rows + 1))
+            })?;
+            row.push(side);
+        }
+        if row.len() != by_rows.sides() {
+            counts.ragged += 1;
+        }
+        counts.part1 += count_block(by_rows, std::slice::from_ref(row));
+
+        counts.rows += 1;
+        if counts.rows % by_columns.block_len() == 0 {
+            counts.part2 += count_block(by_columns, &block);
+        }
+    }
+    counts.leftover = counts.rows % by_columns.block_len();
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1, part2, Day03};
+    use aoc::Solution;
+
+    #[test]
+    fn same_as_parts() {
+        let input = include_str!("../input.txt");
+        let numbers = Day03::parse(input).unwrap();
+        let counts = count::<u32, _>(input.as_bytes()).unwrap();
+        assert_eq!(
+            (counts.part1, counts.part2),
+            (part1(&numbers), part2(&numbers))
+        );
+        assert_eq!((counts.rows, counts.ragged, counts.leftover), (1992, 0, 0));
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let input = "3 4 5\n3 4\n5 5 5 5\n\n10 10 10\n";
+        let counts = count::<u64, _>(input.as_bytes()).unwrap();
+        assert_eq!(
+            counts,
+            Counts {
+                rows: 5,
+                part1: 2,
+                part2: 2,
+                ragged: 3,
+                leftover: 2,
+            }
+        );
+
+        let input = "3 4 5 6\n3 4 5 6\n3 4 5 6\n1 2 3\n4 5\n6 7 8\n5 5 5 5 5";
+        let numbers = Day03::parse(input).unwrap();
+        let counts = count::<u32, _>(input.as_bytes()).unwrap();
+        assert_eq!(
+            (counts.part1, counts.part2),
+            (part1(&numbers), part2(&numbers))
+        );
+        assert_eq!((counts.part1, counts.part2), (1, 3));
+
+        match count::<u32, _>("1 2 3\n4 x 6".as_bytes()) {
+            Err(ParseError::Value(err)) => {
+                assert_eq!(err.position(), aoc::Position { line: 2, column: 3 })
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use aoc::Solution;
+use criterion::{
+    black_box, criterion_group, criterion_main, Criterion, Throughput,
+};
+use day04::{real_id, Day04, Room};
+
+const INPUT: &str = include_str!("../input.txt");
+const GENERATED_ROOMS: u32 = 2_000_000;
+
+fn parts(c: &mut Criterion) {
+    let input = Day04::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day04");
+    group
+        .bench_function("parse", |b| b.iter(|| Day04::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day04::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day04::part2(black_box(&input)))
+    });
+    group.finish();
+}
+
+fn is_valid(c: &mut Criterion) {
+    let rooms: Vec<Room> = Day04::parse(INPUT).unwrap();
+    c.bench_function("day04 Room::is_valid", |b| {
+        b.iter(|| rooms.iter().filter(|room| room.is_valid()).count())
+    });
+}
+
+/// Room lines with random names of three to six words, half of them with
+/// a real checksum, from a fixed seed.
+fn generate(rooms: u32) -> String {
+    let mut seed = 0x2016_0004_u64;
+    let mut next = |bound: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % bound
+    };
+    let mut input = String::new();
+    for id in 0..rooms {
+        let words = 3 + next(4);
+        let name = (0..words)
+            .map(|_| {
+                (0..2 + next(8))
+                    .map(|_| (b'a' + next(26) as u8) as char)
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("-");
+        let checksum = if next(2) == 0 {
+            day04::checksum(&name)
+        } else {
+            "decoy".to_string()
+        };
+        input.push_str(&format!("{}-{}[{}]\n", name, id, checksum));
+    }
+    input
+}
+
+fn generated(c: &mut Criterion) {
+    let input = generate(GENERATED_ROOMS);
+    let mut group = c.benchmark_group("day04 generated");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(GENERATED_ROOMS.into()));
+    group.bench_function("real_id", |b| {
+        b.iter(|| {
+            black_box(&input)
+                .lines()
+                .filter_map(|line| real_id(line).unwrap())
+                .count()
+        })
+    });
+    group.bench_function("parse is_valid", |b| {
+        b.iter(|| {
+            let rooms = Day04::parse(black_box(&input)).unwrap();
+            rooms.iter().filter(|room| room.is_valid()).count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parts, is_valid, generated);
+criterion_main!(benches);
@@ -0,0 +1,240 @@
+//! Breaks the shift cipher of room names without knowing the sector ID, by
+//! scoring every rotation against the letter and bigram frequencies of
+//! English text.
+
+use crate::policy::Alphabet;
+
+/// Frequency of each letter from `a` to `z` in English text, in percent.
+const LETTERS: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153,
+    0.772, 4.025, 2.406, 6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056,
+    2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// Frequency of the most common bigrams in English text, in percent; all
+/// other bigrams are counted together.
+const BIGRAMS: [(&[u8; 2], f64); 50] = [
+    (b"th", 3.56),
+    (b"he", 3.07),
+    (b"in", 2.43),
+    (b"er", 2.05),
+    (b"an", 1.99),
+    (b"re", 1.85),
+    (b"on", 1.76),
+    (b"at", 1.49),
+    (b"en", 1.45),
+    (b"nd", 1.35),
+    (b"ti", 1.34),
+    (b"es", 1.34),
+    (b"or", 1.28),
+    (b"te", 1.20),
+    (b"of", 1.17),
+    (b"ed", 1.17),
+    (b"is", 1.13),
+    (b"it", 1.12),
+    (b"al", 1.09),
+    (b"ar", 1.07),
+    (b"st", 1.05),
+    (b"to", 1.04),
+    (b"nt", 1.04),
+    (b"ng", 0.95),
+    (b"se", 0.93),
+    (b"ha", 0.93),
+    (b"as", 0.87),
+    (b"ou", 0.87),
+    (b"io", 0.83),
+    (b"le", 0.83),
+    (b"ve", 0.83),
+    (b"co", 0.79),
+    (b"me", 0.79),
+    (b"de", 0.76),
+    (b"hi", 0.76),
+    (b"ri", 0.73),
+    (b"ro", 0.73),
+    (b"ic", 0.70),
+    (b"ne", 0.69),
+    (b"ea", 0.69),
+    (b"ra", 0.69),
+    (b"ce", 0.65),
+    (b"li", 0.62),
+    (b"ch", 0.60),
+    (b"ll", 0.58),
+    (b"be", 0.58),
+    (b"ma", 0.57),
+    (b"si", 0.55),
+    (b"om", 0.55),
+    (b"ur", 0.54),
+];
+
+/// A rotation of an encrypted name and how alike English it reads.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// Places each letter is shifted forward, the sector ID modulo the
+    /// period of the alphabet
+    pub shift: u32,
+    pub plaintext: String,
+    /// Pearson's chi-squared statistic of the letter counts; lower is more
+    /// alike English
+    pub chi_squared: f64,
+    /// Natural log of the likelihood of the letters and, within words, the
+    /// bigrams of the plaintext; higher is more alike English
+    pub log_likelihood: f64,
+}
+
+/// Letter and bigram counts of a text, bigrams only within words. Only the
+/// letters `a` to `z` are counted, in either case.
+struct Frequencies {
+    letters: [usize; LETTERS.len()],
+    /// Counts of the bigrams in the table, then of all others together
+    bigrams: [usize; BIGRAMS.len() + 1],
+}
+
+impl Frequencies {
+    fn of(text: &str) -> Self {
+        let mut letters = [0; LETTERS.len()];
+        let mut bigrams = [0; BIGRAMS.len() + 1];
+        let mut previous = None;
+        for byte in text.bytes().map(|byte| byte.to_ascii_lowercase()) {
+            if !byte.is_ascii_lowercase() {
+                previous = None;
+                continue;
+            }
+            letters[(byte - b'a') as usize] += 1;
+            if let Some(first) = previous {
+                let index = BIGRAMS
+                    .iter()
+                    .position(|(known, _)| **known == [first, byte])
+                    .unwrap_or(BIGRAMS.len());
+                bigrams[index] += 1;
+            }
+            previous = Some(byte);
+        }
+        Self { letters, bigrams }
+    }
+
+    fn chi_squared(&self) -> f64 {
+        let total = self.letters.iter().sum::<usize>() as f64;
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.letters
+            .iter()
+            .zip(&LETTERS)
+            .map(|(&count, &percent)| {
+                let expected = total * percent / 100.0;
+                (count as f64 - expected).powi(2) / expected
+            })
+            .sum()
+    }
+
+    fn log_likelihood(&self) -> f64 {
+        let known = BIGRAMS.iter().map(|&(_, percent)| percent);
+        let other = 100.0 - known.clone().sum::<f64>();
+        // Bigrams not in the table share what is left evenly
+        let other = other / (LETTERS.len().pow(2) - BIGRAMS.len()) as f64;
+        log_sum(&self.letters, LETTERS.iter().copied())
+            + log_sum(&self.bigrams, known.chain(Some(other)))
+    }
+}
+
+/// Natural log of the likelihood of `counts` occurrences of outcomes with
+/// these frequencies, in percent.
+fn log_sum(counts: &[usize], percents: impl Iterator<Item = f64>) -> f64 {
+    counts
+        .iter()
+        .zip(percents)
+        .map(|(&count, percent)| count as f64 * (percent / 100.0).ln())
+        .sum()
+}
+
+/// Every rotation of an encrypted name through `alphabet`, most likely
+/// first. Names are too short for chi-squared alone to tell English apart,
+/// with most letters expected less than once, so rotations are ranked by
+/// the likelihood of their letters and bigrams, then by chi-squared.
+pub fn rank(alphabet: &Alphabet, name: &str) -> Vec<Candidate> {
+    let mut candidates = (0..alphabet.period())
+        .map(|shift| {
+            let plaintext = alphabet.decrypt(name, shift);
+            let frequencies = Frequencies::of(&plaintext);
+            Candidate {
+                shift,
+                plaintext,
+                chi_squared: frequencies.chi_squared(),
+                log_likelihood: frequencies.log_likelihood(),
+            }
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| {
+        b.log_likelihood
+            .partial_cmp(&a.log_likelihood)
+            .unwrap()
+            .then(a.chi_squared.partial_cmp(&b.chi_squared).unwrap())
+            .then(a.shift.cmp(&b.shift))
+    });
+    candidates
+}
+
+/// The most likely rotation of an encrypted name through `alphabet`.
+pub fn crack(alphabet: &Alphabet, name: &str) -> Candidate {
+    rank(alphabet, name).swap_remove(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::ChecksumPolicy;
+    use crate::{Day04, Room};
+    use aoc::Solution;
+
+    #[test]
+    fn cracks() {
+        let lowercase = Alphabet::lowercase();
+        let room = Room::encrypt("northpole object storage", 501).unwrap();
+        let best = crack(&lowercase, room.name());
+        assert_eq!(best.shift, 501 % 26);
+        assert_eq!(best.plaintext, "northpole object storage");
+
+        let ranked = rank(&lowercase, "qzmt-zixmtkozy-ivhz");
+        assert_eq!(ranked.len(), 26);
+        assert_eq!(ranked[0].plaintext, "very encrypted name");
+        assert!(ranked
+            .windows(2)
+            .all(|w| w[0].log_likelihood >= w[1].log_likelihood));
+        assert!(ranked[0].chi_squared < ranked[1].chi_squared);
+        assert_eq!(crack(&lowercase, "").log_likelihood, 0.0);
+
+        let cased = ChecksumPolicy {
+            alphabet: Alphabet::cased(),
+            ..ChecksumPolicy::default()
+        };
+        let room = cased.encrypt("Northpole Object Storage", 501).unwrap();
+        let best = crack(&cased.alphabet, room.name());
+        assert_eq!(best.plaintext, "Northpole Object Storage");
+
+        let greek = Alphabet::new(&["αβγδεζηθικλμνξοπρστυφχψω"]).unwrap();
+        // No letter of a Greek name is scored, so every rotation is as
+        // likely and they are left in order of shift
+        let ranked = rank(&greek, "αβγ");
+        assert_eq!(ranked.len(), 24);
+        assert!(ranked.iter().all(|candidate| {
+            candidate.log_likelihood == 0.0 && candidate.chi_squared == 0.0
+        }));
+        assert!(ranked.iter().map(|c| c.shift).eq(0..24));
+        assert_eq!(ranked[1].plaintext, "βγδ");
+        let odd = Alphabet::new(&["abcd", "efghij"]).unwrap();
+        assert_eq!(odd.period(), 12);
+    }
+
+    #[test]
+    fn cracks_most_rooms() {
+        let lowercase = Alphabet::lowercase();
+        let rooms = Day04::parse(include_str!("../input.txt")).unwrap();
+        let real = rooms.iter().filter(|room| room.is_valid());
+        let (cracked, total) = real.fold((0, 0), |(cracked, total), room| {
+            let best = crack(&lowercase, room.name());
+            let right = best.shift == room.id() % 26;
+            (cracked + right as usize, total + 1)
+        });
+        assert!(cracked * 100 >= total * 99, "{} of {}", cracked, total);
+    }
+}
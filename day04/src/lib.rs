@@ -1,10 +1,12 @@
-use counter::Counter;
-use regex::Regex;
-use std::io::{Error, ErrorKind};
-use std::str::FromStr;
+pub mod cipher;
+pub mod policy;
+pub mod query;
 
-const CHAR_A: u32 = 'a' as u32;
-const ALPHA_LEN: u32 = 'z' as u32 - CHAR_A + 1;
+use aoc::{parse_lines, Error, Solution};
+use policy::{Alphabet, ChecksumPolicy};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 pub struct Room {
     name: String,
@@ -12,39 +14,59 @@ pub struct Room {
     checksum: String,
 }
 
+/// The checksum policy of the puzzle, built once.
+fn puzzle_policy() -> &'static ChecksumPolicy {
+    static POLICY: OnceLock<ChecksumPolicy> = OnceLock::new();
+    POLICY.get_or_init(ChecksumPolicy::default)
+}
+
+/// The five most common letters of an encrypted name, ties broken
+/// alphabetically.
+pub fn checksum(name: &str) -> String {
+    puzzle_policy().checksum(name)
+}
+
+/// The sector ID of a room line if it is a real room, validated without
+/// allocating.
+pub fn real_id(line: &str) -> Result<Option<u32>, Error> {
+    puzzle_policy().real_id(line)
+}
+
 impl Room {
-    fn is_valid(&self) -> bool {
-        let char_counts = self
-            .name
-            .chars()
-            .filter(|&ch| ch != '-')
-            .collect::<Counter<_>>();
-        let mut top_chars = char_counts.iter().collect::<Vec<_>>();
-        top_chars.sort_unstable_by(|(ch_a, count_a), (ch_b, count_b)| {
-            count_b.cmp(count_a).then(ch_a.cmp(ch_b))
-        });
-        self.checksum
-            == top_chars
-                .iter()
-                .take(5)
-                .map(|&(ch, _)| *ch)
-                .collect::<String>()
+    /// A real room with the given decrypted name, made of lowercase words
+    /// separated by spaces or dashes, and sector ID.
+    pub fn encrypt(name: &str, id: u32) -> Result<Self, Error> {
+        puzzle_policy().encrypt(name, id)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    /// Whether the checksum lists the five most common letters of the name,
+    /// ties broken alphabetically.
+    pub fn is_valid(&self) -> bool {
+        self.is_valid_with(puzzle_policy())
+    }
+
+    pub fn is_valid_with(&self, policy: &ChecksumPolicy) -> bool {
+        policy.is_valid(&self.name, &self.checksum)
+    }
+
+    pub fn decrypt(&self) -> String {
+        self.decrypt_with(&puzzle_policy().alphabet)
     }
 
-    fn decrypt(&self) -> String {
-        self.name
-            .chars()
-            .map(|ch| {
-                if ch.is_ascii_lowercase() {
-                    ((CHAR_A + (ch as u32 - CHAR_A + self.id) % ALPHA_LEN)
-                        as u8) as char
-                } else if ch == '-' {
-                    ' '
-                } else {
-                    ch
-                }
-            })
-            .collect()
+    pub fn decrypt_with(&self, alphabet: &Alphabet) -> String {
+        alphabet.decrypt(&self.name, self.id)
     }
 }
 
@@ -64,26 +86,90 @@ pub fn part2(rooms: &[Room]) -> u32 {
         .unwrap_or(0)
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Room>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    const DAY: u32 = 4;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input)
+    }
+
+    fn part1(rooms: &Self::Input) -> Result<Option<u32>, Error> {
+        Ok(Some(part1(rooms)))
+    }
+
+    fn part2(rooms: &Self::Input) -> Result<Option<u32>, Error> {
+        Ok(Some(part2(rooms)))
+    }
+}
+
+impl Display for Room {
+    /// Writes the room as a line of the puzzle input.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}[{}]", self.name, self.id, self.checksum)
+    }
+}
+
 impl FromStr for Room {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pattern =
-            Regex::new(r"^([[:alpha:]-]+)-([0-9]+)\[([[:alpha:]]+)\]").unwrap();
+        puzzle_policy().parse(s)
+    }
+}
 
-        let groups = pattern.captures(s).ok_or_else(|| {
-            Error::new(ErrorKind::InvalidData, "Invalid format")
-        })?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let name = groups.get(1).unwrap().as_str().to_string();
-        let id = groups
-            .get(2)
+    #[test]
+    fn encryption() {
+        let room = Room::encrypt("very encrypted name", 343).unwrap();
+        assert_eq!(room.name(), "qzmt-zixmtkozy-ivhz");
+        assert_eq!(room.to_string(), "qzmt-zixmtkozy-ivhz-343[zimth]");
+
+        let room: Room = Room::encrypt("northpole-object storage", 26)
             .unwrap()
-            .as_str()
+            .to_string()
             .parse()
-            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
-        let checksum = groups.get(3).unwrap().as_str().to_string();
+            .unwrap();
+        assert!(room.is_valid());
+        assert_eq!(room.decrypt(), "northpole object storage");
+
+        assert_eq!(checksum("not-a-real-room"), "oarel");
+        let err = Room::encrypt("North Pole", 1).err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 1, column: 1 });
+        assert!(Room::encrypt("- -", 1).is_err());
+        let room = Room::encrypt("abc", u32::MAX).unwrap();
+        assert_eq!(room.decrypt(), "abc");
+    }
+
+    #[test]
+    fn validation() {
+        let input = "aaaaa-bbb-z-y-x-123[abxyz]\n\
+                     a-b-c-d-e-f-g-h-987[abcde]\n\
+                     not-a-real-room-404[oarel]\n\
+                     totally-real-room-200[decoy]";
+        let ids = input
+            .lines()
+            .map(|line| real_id(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![Some(123), Some(987), Some(404), None]);
+        let rooms = Day04::parse(input).unwrap();
+        assert_eq!(part1(&rooms), 1514);
+        assert_eq!(real_id("a-b-1[ab]trailing").unwrap(), Some(1));
 
-        Ok(Room { name, id, checksum })
+        for line in &["abc-1", "abc1[abc]", "-1[a]", "abc-[abc]", "a-1[]"] {
+            let err = real_id(line).err().unwrap();
+            assert_eq!(err.reason(), "Invalid room", "{}", line);
+        }
+        let err = Day04::parse("a-1[a]\nabc-99999999999[abc]").err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 2, column: 5 });
+        assert!(real_id("Abc-1[abc]").is_err());
     }
 }
@@ -1,53 +1,215 @@
-use clap::{crate_description, App, Arg};
-use day04::{part1, part2, Room};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Error, ErrorKind};
+use aoc::answers::Answers;
+use aoc::input::read_input;
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, run, stats_arg,
+    Format, Part,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::Solution;
+use clap::{
+    crate_description, value_t_or_exit, App, Arg, ArgMatches, SubCommand,
+};
+use day04::cipher::rank;
+use day04::policy::Alphabet;
+use day04::query::{search, Query};
+use day04::{Day04, Room};
 use std::process::exit;
 
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
 fn main() {
     let args = App::new(crate_description!())
         .arg(
             Arg::with_name("INPUT")
-                .help("Sets the input file to use")
-                .required(true)
+                .help("Sets the input file to use [default: cached input]")
                 .index(1),
         )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
+        .arg(
+            Arg::with_name("ENCRYPT")
+                .long("encrypt")
+                .takes_value(true)
+                .value_name("NAME")
+                .requires("ID")
+                .help("Prints a real room with this decrypted name"),
+        )
+        .arg(
+            Arg::with_name("ID")
+                .long("id")
+                .takes_value(true)
+                .requires("ENCRYPT")
+                .help("Sets the sector ID of the room to encrypt"),
+        )
+        .arg(
+            Arg::with_name("CRACK")
+                .long("crack")
+                .takes_value(true)
+                .value_name("NAME")
+                .conflicts_with("ENCRYPT")
+                .help("Ranks the rotations of an encrypted name, most likely first"),
+        )
+        .subcommand(
+            SubCommand::with_name("rooms")
+                .about("Lists the real rooms by sector ID, decrypted")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .help(
+                            "Sets the input file to use \
+                             [default: cached input]",
+                        )
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("CONTAINS")
+                        .long("contains")
+                        .takes_value(true)
+                        .value_name("TEXT")
+                        .help("Lists only rooms with this text in their names"),
+                )
+                .arg(
+                    Arg::with_name("MATCHES")
+                        .long("matches")
+                        .takes_value(true)
+                        .value_name("REGEX")
+                        .help("Lists only rooms with names matching a regex"),
+                )
+                .arg(
+                    Arg::with_name("WORDS")
+                        .long("words")
+                        .takes_value(true)
+                        .help(
+                        "Lists only rooms with all these words in their names",
+                    ),
+                )
+                .arg(format_arg()),
+        )
         .get_matches();
 
-    println!(crate_description!());
-    let rooms = read_input(args.value_of("INPUT").unwrap());
-    println!("Part 1: {}", part1(&rooms));
-    println!("Part 2: {}", part2(&rooms));
+    if let Some(rooms_args) = args.subcommand_matches("rooms") {
+        list_rooms(rooms_args);
+        exit(0);
+    }
+
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    if let Some(name) = args.value_of("ENCRYPT") {
+        let id = value_t_or_exit!(args.value_of("ID"), u32);
+        let room = Room::encrypt(name, id).unwrap_or_else(exit_with_error(
+            Day04::DAY,
+            format,
+            1,
+        ));
+        print_room(&room, format);
+        exit(0);
+    }
+    if let Some(name) = args.value_of("CRACK") {
+        print_rotations(name, format);
+        exit(0);
+    }
+
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+    let (contents, filename) = read_input(args.value_of("INPUT"), Day04::DAY)
+        .unwrap_or_else(exit_with_error(Day04::DAY, format, 2));
+    let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+        .unwrap_or_else(exit_with_error(Day04::DAY, format, 2));
+
+    let reports =
+        answers.check(run::<Day04>(&contents, &[Part::One, Part::Two]));
+    for report in &reports {
+        report.print(format, stats);
+    }
+    exit(exit_code(&reports));
 }
 
-fn read_input(filename: &str) -> Vec<Room> {
-    let file = match File::open(filename) {
-        Ok(file) => file,
-        Err(err) => {
-            println!("Failed to open file '{}': {}", filename, err.to_string());
-            exit(2);
-        }
-    };
-
-    match BufReader::new(file)
-        .lines()
-        .map(|line| {
-            line.and_then(|value| {
-                value
-                    .parse()
-                    .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+fn print_room(room: &Room, format: Format) {
+    if format == Format::Json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "room": room.to_string(),
+                "name": room.name(),
+                "id": room.id(),
+                "checksum": room.checksum(),
             })
-        })
-        .collect()
-    {
-        Ok(rooms) => rooms,
-        Err(err) => {
-            println!(
-                "Failed to parse input file '{}': {}",
-                filename,
-                err.to_string()
-            );
-            exit(3);
-        }
+        );
+        return;
+    }
+    println!("{}", room);
+}
+
+/// Prints every rotation of an encrypted name, most likely first.
+fn print_rotations(name: &str, format: Format) {
+    let candidates = rank(&Alphabet::lowercase(), name);
+    if format == Format::Json {
+        let candidates = candidates
+            .iter()
+            .map(|candidate| {
+                serde_json::json!({
+                    "shift": candidate.shift,
+                    "plaintext": candidate.plaintext,
+                    "log_likelihood": candidate.log_likelihood,
+                    "chi_squared": candidate.chi_squared,
+                })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::Value::Array(candidates));
+        return;
+    }
+    println!("Shift  Log-likelihood  Chi-squared  Plaintext");
+    for candidate in &candidates {
+        println!(
+            "{:>5}  {:>14.2}  {:>11.2}  {}",
+            candidate.shift,
+            candidate.log_likelihood,
+            candidate.chi_squared,
+            candidate.plaintext
+        );
+    }
+}
+
+/// Prints the real rooms matching the queries given in `args`.
+fn list_rooms(args: &ArgMatches) {
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let mut queries = Vec::new();
+    if let Some(text) = args.value_of("CONTAINS") {
+        queries.push(Query::Contains(text.to_string()));
+    }
+    if let Some(pattern) = args.value_of("MATCHES") {
+        queries.push(Query::pattern(pattern).unwrap_or_else(exit_with_error(
+            Day04::DAY,
+            format,
+            1,
+        )));
+    }
+    if let Some(words) = args.value_of("WORDS") {
+        queries.push(Query::words(words));
+    }
+
+    let (contents, _) = read_input(args.value_of("INPUT"), Day04::DAY)
+        .unwrap_or_else(exit_with_error(Day04::DAY, format, 2));
+    let rooms = Day04::parse(&contents).unwrap_or_else(exit_with_error(
+        Day04::DAY,
+        format,
+        3,
+    ));
+
+    let found = search(&rooms, &queries);
+    if format == Format::Json {
+        let rooms = found
+            .iter()
+            .map(|found| {
+                serde_json::json!({ "id": found.room.id(), "name": found.name })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::Value::Array(rooms));
+        return;
+    }
+    for found in &found {
+        println!("{:>6} {}", found.room.id(), found.name);
     }
 }
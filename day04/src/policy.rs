@@ -0,0 +1,365 @@
+//! The rules room checksums follow: how many letters they have, how ties
+//! between equally common letters are broken and which letters names are
+//! made of.
+
+use crate::Room;
+use aoc::{column_at, Error};
+
+/// Most letters an alphabet can have, so that they can be counted and the
+/// most common selected without allocating; `Alphabet::new` fails with
+/// more.
+pub const MAX_LETTERS: usize = 64;
+
+/// The letters of room names in alphabetical order, split into cases that
+/// each rotate on their own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+    /// Index of the first letter of every case, then the number of letters
+    cases: Vec<usize>,
+    /// Index of every ASCII letter, for a quick look-up
+    ascii: [Option<u8>; 128],
+}
+
+impl Alphabet {
+    /// An alphabet with the letters of every case in alphabetical order,
+    /// cases in order too.
+    pub fn new(cases: &[&str]) -> Result<Self, Error> {
+        let mut alphabet = Self {
+            letters: Vec::new(),
+            cases: vec![0],
+            ascii: [None; 128],
+        };
+        for case in cases {
+            if case.is_empty() {
+                return Err(Error::value(1, *case, "Case without letters"));
+            }
+            for (index, ch) in case.chars().enumerate() {
+                let reason = if !ch.is_alphabetic() {
+                    "Not a letter"
+                } else if alphabet.letters.contains(&ch) {
+                    "Repeated letter"
+                } else if alphabet.letters.len() == MAX_LETTERS {
+                    "Too many letters"
+                } else {
+                    if ch.is_ascii() {
+                        alphabet.ascii[ch as usize] =
+                            Some(alphabet.letters.len() as u8);
+                    }
+                    alphabet.letters.push(ch);
+                    continue;
+                };
+                return Err(Error::value(index + 1, ch, reason));
+            }
+            alphabet.cases.push(alphabet.letters.len());
+        }
+        if alphabet.letters.is_empty() {
+            return Err(Error::value(1, "", "Alphabet without letters"));
+        }
+        Ok(alphabet)
+    }
+
+    /// The letters `a` to `z` of the puzzle.
+    pub fn lowercase() -> Self {
+        Self::new(&["abcdefghijklmnopqrstuvwxyz"]).unwrap()
+    }
+
+    /// The letters `A` to `Z`, then `a` to `z`, telling cases apart.
+    pub fn cased() -> Self {
+        Self::new(&["ABCDEFGHIJKLMNOPQRSTUVWXYZ", "abcdefghijklmnopqrstuvwxyz"])
+            .unwrap()
+    }
+
+    fn index(&self, ch: char) -> Option<usize> {
+        if ch.is_ascii() {
+            self.ascii[ch as usize].map(usize::from)
+        } else {
+            self.letters.iter().position(|&letter| letter == ch)
+        }
+    }
+
+    pub fn contains(&self, ch: char) -> bool {
+        self.index(ch).is_some()
+    }
+
+    /// Shifts a letter `by` places through its case, forward or, if
+    /// negative, backward; any other character is kept.
+    pub fn rotate(&self, ch: char, by: i64) -> char {
+        let index = match self.index(ch) {
+            Some(index) => index,
+            None => return ch,
+        };
+        let case = self.cases.windows(2).find(|case| index < case[1]).unwrap();
+        let (start, len) = (case[0], case[1] - case[0]);
+        let offset = (index - start) as i64 + by.rem_euclid(len as i64);
+        self.letters[start + (offset % len as i64) as usize]
+    }
+
+    /// How many distinct rotations there are: rotating by this many places
+    /// brings every case back to where it started.
+    pub fn period(&self) -> u32 {
+        self.cases
+            .windows(2)
+            .map(|case| (case[1] - case[0]) as u32)
+            .fold(1, |period, len| period / gcd(period, len) * len)
+    }
+
+    /// Rotates every letter of an encrypted name forward by the sector ID,
+    /// turning dashes into spaces.
+    pub fn decrypt(&self, name: &str, id: u32) -> String {
+        name.chars()
+            .map(|ch| match ch {
+                '-' => ' ',
+                _ => self.rotate(ch, id.into()),
+            })
+            .collect()
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::lowercase()
+    }
+}
+
+/// How ties between equally common letters are broken.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Ties {
+    /// The first letter in the alphabet goes first
+    Alphabetical,
+    /// The last letter in the alphabet goes first
+    Reverse,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChecksumPolicy {
+    /// Most letters in a checksum, fewer if names have fewer letters
+    pub length: usize,
+    pub ties: Ties,
+    pub alphabet: Alphabet,
+}
+
+impl Default for ChecksumPolicy {
+    /// The five most common lowercase letters, ties broken alphabetically.
+    fn default() -> Self {
+        Self {
+            length: 5,
+            ties: Ties::Alphabetical,
+            alphabet: Alphabet::lowercase(),
+        }
+    }
+}
+
+impl ChecksumPolicy {
+    /// The checksum letters of an encrypted name, most common first. Letters
+    /// are counted into one slot each and the most common are selected
+    /// without sorting all of them or allocating.
+    fn letters<'a>(&'a self, name: &str) -> impl Iterator<Item = char> + 'a {
+        let mut counts = [0_u32; MAX_LETTERS];
+        for index in name.chars().filter_map(|ch| self.alphabet.index(ch)) {
+            counts[index] += 1;
+        }
+        let len = self.alphabet.letters.len();
+        let top_len = self.length.min(len);
+        // Visited in the order ties are broken, so a letter only goes ahead
+        // of those with fewer occurrences
+        let mut top = [(0_u32, 0_u8); MAX_LETTERS];
+        let mut visit = |index: usize| {
+            let count = counts[index];
+            if top_len == 0 || count <= top[top_len - 1].0 {
+                return;
+            }
+            let rank = top[..top_len]
+                .iter()
+                .position(|&(top_count, _)| count > top_count)
+                .unwrap();
+            top.copy_within(rank..top_len - 1, rank + 1);
+            top[rank] = (count, index as u8);
+        };
+        match self.ties {
+            Ties::Alphabetical => (0..len).for_each(&mut visit),
+            Ties::Reverse => (0..len).rev().for_each(&mut visit),
+        }
+        IntoIterator::into_iter(top)
+            .take(top_len)
+            .take_while(|&(count, _)| count > 0)
+            .map(move |(_, index)| self.alphabet.letters[usize::from(index)])
+    }
+
+    pub fn checksum(&self, name: &str) -> String {
+        self.letters(name).collect()
+    }
+
+    /// Whether `checksum` lists the most common letters of the name.
+    pub fn is_valid(&self, name: &str, checksum: &str) -> bool {
+        self.letters(name).eq(checksum.chars())
+    }
+
+    /// A real room with the given decrypted name, made of words separated by
+    /// spaces or dashes, and sector ID.
+    pub fn encrypt(&self, name: &str, id: u32) -> Result<Room, Error> {
+        let mut encrypted = String::with_capacity(name.len());
+        for (index, ch) in name.chars().enumerate() {
+            encrypted.push(match ch {
+                ' ' | '-' => '-',
+                _ if self.alphabet.contains(ch) => {
+                    self.alphabet.rotate(ch, -i64::from(id))
+                }
+                _ => {
+                    return Err(Error::value(
+                        index + 1,
+                        ch,
+                        "Invalid character in room name",
+                    ))
+                }
+            });
+        }
+        if !encrypted.chars().any(|ch| self.alphabet.contains(ch)) {
+            return Err(Error::value(1, name, "Room name without letters"));
+        }
+        let checksum = self.checksum(&encrypted);
+        Ok(Room {
+            name: encrypted,
+            id,
+            checksum,
+        })
+    }
+
+    /// Splits a room line into its encrypted name, sector ID and checksum,
+    /// borrowing them from the line.
+    fn split<'a>(
+        &self,
+        line: &'a str,
+    ) -> Result<(&'a str, u32, &'a str), Error> {
+        let invalid = || Error::format(1, line, "Invalid room");
+        let name_len = line
+            .char_indices()
+            .find(|&(_, ch)| ch != '-' && !self.alphabet.contains(ch))
+            .map_or(line.len(), |(index, _)| index);
+        // The name takes the dashes too, so it must end with the one before
+        // the sector ID
+        if name_len < 2 || !line[..name_len].ends_with('-') {
+            return Err(invalid());
+        }
+        let id_len = line[name_len..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        let id_text = &line[name_len..name_len + id_len];
+        if id_text.is_empty() {
+            return Err(invalid());
+        }
+        let checksum = line[name_len + id_len..]
+            .strip_prefix('[')
+            .and_then(|rest| rest.find(']').map(|end| &rest[..end]))
+            .filter(|checksum| {
+                !checksum.is_empty()
+                    && checksum.chars().all(|ch| self.alphabet.contains(ch))
+            })
+            .ok_or_else(invalid)?;
+        let id = id_text.parse().map_err(|err| {
+            Error::value(column_at(line, name_len), id_text, err)
+        })?;
+        Ok((&line[..name_len - 1], id, checksum))
+    }
+
+    pub fn parse(&self, line: &str) -> Result<Room, Error> {
+        let (name, id, checksum) = self.split(line)?;
+        Ok(Room {
+            name: name.to_string(),
+            id,
+            checksum: checksum.to_string(),
+        })
+    }
+
+    /// The sector ID of a room line if it is a real room, validated without
+    /// allocating.
+    pub fn real_id(&self, line: &str) -> Result<Option<u32>, Error> {
+        let (name, id, checksum) = self.split(line)?;
+        Ok(Some(id).filter(|_| self.is_valid(name, checksum)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies() {
+        let shorter = ChecksumPolicy {
+            length: 3,
+            ..ChecksumPolicy::default()
+        };
+        assert_eq!(
+            shorter.real_id("aaaaa-bbb-z-y-x-123[abx]").unwrap(),
+            Some(123)
+        );
+        let reverse = ChecksumPolicy {
+            ties: Ties::Reverse,
+            ..ChecksumPolicy::default()
+        };
+        assert_eq!(reverse.checksum("aaaaa-bbb-z-y-x"), "abzyx");
+        assert_eq!(
+            reverse.real_id("a-b-c-d-e-f-g-h-987[hgfed]").unwrap(),
+            Some(987)
+        );
+
+        let cased = ChecksumPolicy {
+            alphabet: Alphabet::cased(),
+            ..ChecksumPolicy::default()
+        };
+        let room = cased.parse("NorthPole-Object-7[eotNO]").unwrap();
+        assert!(room.is_valid_with(&cased));
+        assert_eq!(room.decrypt_with(&cased.alphabet), "UvyaoWvsl Viqlja");
+        assert!(ChecksumPolicy::default().parse("NorthPole-7[ob]").is_err());
+
+        let greek = ChecksumPolicy {
+            length: 2,
+            alphabet: Alphabet::new(&["αβγδεζηθικλμνξοπρστυφχψω"]).unwrap(),
+            ..ChecksumPolicy::default()
+        };
+        let room = greek.encrypt("αλφα βητα", 25).unwrap();
+        assert_eq!(room.to_string(), "ωκυω-αζσω-25[ωα]");
+        assert!(greek
+            .parse(&room.to_string())
+            .unwrap()
+            .is_valid_with(&greek));
+        assert_eq!(room.decrypt_with(&greek.alphabet), "αλφα βητα");
+        assert!(greek.encrypt("alpha", 1).is_err());
+
+        let err = Alphabet::new(&["abca"]).err().unwrap();
+        assert_eq!(
+            (err.position().column, err.reason()),
+            (4, "Repeated letter")
+        );
+        assert!(Alphabet::new(&["ab", ""]).is_err());
+        assert!(Alphabet::new(&["a1"]).is_err());
+        assert!(Alphabet::new(&[]).is_err());
+
+        let many = ('A'..='Z')
+            .chain('a'..='z')
+            .chain('α'..='ω')
+            .collect::<String>();
+        let err = Alphabet::new(&[&many]).err().unwrap();
+        assert_eq!(
+            (err.position().column, err.reason()),
+            (MAX_LETTERS + 1, "Too many letters")
+        );
+        let most = many.chars().take(MAX_LETTERS).collect::<String>();
+        let most = Alphabet::new(&[&most]).unwrap();
+        let longest = ChecksumPolicy {
+            length: 100,
+            alphabet: most,
+            ..ChecksumPolicy::default()
+        };
+        assert_eq!(longest.checksum("zz-ab-Zb"), "bzZa");
+    }
+}
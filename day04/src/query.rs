@@ -0,0 +1,98 @@
+//! Searches the decrypted names of real rooms.
+
+use crate::Room;
+use aoc::Error;
+use regex::Regex;
+
+pub enum Query {
+    /// Names with this text anywhere in them
+    Contains(String),
+    /// Names matched by a regular expression
+    Matches(Regex),
+    /// Names with all of these words, in any order
+    Words(Vec<String>),
+}
+
+impl Query {
+    pub fn pattern(pattern: &str) -> Result<Self, Error> {
+        Regex::new(pattern)
+            .map(Self::Matches)
+            .map_err(|err| Error::format(1, pattern, err))
+    }
+
+    /// A query for the whitespace-separated words of `text`.
+    pub fn words(text: &str) -> Self {
+        Self::Words(text.split_whitespace().map(String::from).collect())
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Contains(text) => name.contains(text.as_str()),
+            Self::Matches(pattern) => pattern.is_match(name),
+            Self::Words(words) => words
+                .iter()
+                .all(|word| name.split(' ').any(|name_word| name_word == word)),
+        }
+    }
+}
+
+/// A real room found by a search, with its decrypted name.
+pub struct Found<'a> {
+    pub room: &'a Room,
+    pub name: String,
+}
+
+/// The real rooms whose decrypted names match every query, by sector ID;
+/// decoys are left out, since their names don't decrypt to anything.
+pub fn search<'a>(rooms: &'a [Room], queries: &[Query]) -> Vec<Found<'a>> {
+    let mut found = rooms
+        .iter()
+        .filter(|room| room.is_valid())
+        .map(|room| Found {
+            room,
+            name: room.decrypt(),
+        })
+        .filter(|found| queries.iter().all(|query| query.is_match(&found.name)))
+        .collect::<Vec<_>>();
+    found.sort_by(|a, b| (a.room.id(), &a.name).cmp(&(b.room.id(), &b.name)));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day04;
+    use aoc::Solution;
+
+    #[test]
+    fn searches() {
+        let rooms = Day04::parse(
+            "qzmt-zixmtkozy-ivhz-343[zimth]\n\
+             totally-real-room-200[decoy]\n\
+             ghkmaihex-hucxvm-lmhktzx-501[hmxka]\n\
+             ifmmp-xpsme-25[mpefi]",
+        )
+        .unwrap();
+        let names = |queries: &[Query]| {
+            search(&rooms, queries)
+                .iter()
+                .map(|found| (found.room.id(), found.name.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(&[]),
+            vec![
+                (25, "hello world".to_string()),
+                (343, "very encrypted name".to_string()),
+                (501, "northpole object storage".to_string()),
+            ]
+        );
+        assert_eq!(names(&[Query::Contains("pole".to_string())]).len(), 1);
+        assert_eq!(names(&[Query::words("name very")])[0].0, 343);
+        assert!(names(&[Query::words("pole")]).is_empty());
+        let pattern = Query::pattern("^[a-z]+ (world|name)$").unwrap();
+        assert_eq!(names(&[pattern])[0].0, 25);
+        assert!(Query::pattern("(").is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use aoc::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day05::Day05;
+
+const INPUT: &str = include_str!("../input.txt");
+
+fn parts(c: &mut Criterion) {
+    let input = Day05::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day05");
+    // Each iteration takes seconds, so keep the number of samples low
+    group.sample_size(10);
+    group
+        .bench_function("parse", |b| b.iter(|| Day05::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day05::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day05::part2(black_box(&input)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parts);
+criterion_main!(benches);
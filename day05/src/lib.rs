@@ -1,3 +1,4 @@
+use aoc::{Error, Solution};
 use md5::Context;
 use std::char::from_digit;
 use std::collections::BTreeMap;
@@ -43,3 +44,25 @@ pub fn part2(door_id: &str) -> String {
     }
     password.values().collect()
 }
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = String;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    const DAY: u32 = 5;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(door_id: &Self::Input) -> Result<Option<String>, Error> {
+        Ok(Some(part1(door_id)))
+    }
+
+    fn part2(door_id: &Self::Input) -> Result<Option<String>, Error> {
+        Ok(Some(part2(door_id)))
+    }
+}
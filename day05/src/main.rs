@@ -1,18 +1,57 @@
-use clap::{crate_description, App, Arg};
-use day05::{part1, part2};
+use aoc::answers::Answers;
+use aoc::input::read_input;
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, run, stats_arg,
+    Format, Part,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::Solution;
+use clap::{crate_description, value_t_or_exit, App, Arg};
+use day05::Day05;
+use std::path::Path;
+use std::process::exit;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
 
 fn main() {
     let args = App::new(crate_description!())
         .arg(
             Arg::with_name("DOOR_ID")
-                .help("The problem input")
-                .required(true)
+                .help("The problem input [default: cached input]")
                 .index(1),
         )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
         .get_matches();
 
-    println!(crate_description!());
-    let door_id = args.value_of("DOOR_ID").unwrap();
-    println!("Part 1: {}", part1(&door_id));
-    println!("Part 2: {}", part2(&door_id));
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+    let (door_id, answers) = match args.value_of("DOOR_ID") {
+        Some(door_id) => {
+            let answers = match args.value_of("ANSWERS") {
+                Some(path) => Answers::load(Path::new(path)),
+                None => Ok(Answers::default()),
+            };
+            (door_id.to_string(), answers)
+        }
+        None => {
+            let (door_id, filename) = read_input(None, Day05::DAY)
+                .unwrap_or_else(exit_with_error(Day05::DAY, format, 2));
+            (door_id, Answers::find(args.value_of("ANSWERS"), &filename))
+        }
+    };
+    let answers =
+        answers.unwrap_or_else(exit_with_error(Day05::DAY, format, 2));
+
+    let reports =
+        answers.check(run::<Day05>(&door_id, &[Part::One, Part::Two]));
+    for report in &reports {
+        report.print(format, stats);
+    }
+    exit(exit_code(&reports));
 }
@@ -0,0 +1,22 @@
+use aoc::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day06::Day06;
+
+const INPUT: &str = include_str!("../input.txt");
+
+fn parts(c: &mut Criterion) {
+    let input = Day06::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day06");
+    group
+        .bench_function("parse", |b| b.iter(|| Day06::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day06::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day06::part2(black_box(&input)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parts);
+criterion_main!(benches);
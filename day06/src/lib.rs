@@ -1,3 +1,4 @@
+use aoc::{Error, Solution};
 use counter::Counter;
 use std::collections::BTreeMap;
 
@@ -21,7 +22,7 @@ pub fn part1(counters: &BTreeMap<usize, Counter<char, usize>>) -> String {
     counters
         .values()
         .filter_map(|counter| {
-            counter.most_common().iter().map(|&(ch, _)| ch).nth(0)
+            counter.most_common().iter().map(|&(ch, _)| ch).next()
         })
         .collect()
 }
@@ -30,7 +31,30 @@ pub fn part2(counters: &BTreeMap<usize, Counter<char, usize>>) -> String {
     counters
         .values()
         .filter_map(|counter| {
-            counter.most_common().iter().map(|&(ch, _)| ch).last()
+            counter.most_common().iter().map(|&(ch, _)| ch).next_back()
         })
         .collect()
 }
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = BTreeMap<usize, Counter<char, usize>>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    const DAY: u32 = 6;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let messages = input.lines().map(String::from).collect::<Vec<_>>();
+        Ok(count_chars(&messages))
+    }
+
+    fn part1(counters: &Self::Input) -> Result<Option<String>, Error> {
+        Ok(Some(part1(counters)))
+    }
+
+    fn part2(counters: &Self::Input) -> Result<Option<String>, Error> {
+        Ok(Some(part2(counters)))
+    }
+}
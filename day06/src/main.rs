@@ -1,44 +1,44 @@
-use clap::{crate_description, App, Arg};
-use day06::{count_chars, part1, part2};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use aoc::answers::Answers;
+use aoc::input::read_input;
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, run, stats_arg,
+    Format, Part,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::Solution;
+use clap::{crate_description, value_t_or_exit, App, Arg};
+use day06::Day06;
 use std::process::exit;
 
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
 fn main() {
     let args = App::new(crate_description!())
         .arg(
             Arg::with_name("INPUT")
-                .help("Sets the input file to use")
-                .required(true)
+                .help("Sets the input file to use [default: cached input]")
                 .index(1),
         )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
         .get_matches();
 
-    println!(crate_description!());
-    let messages = read_input(args.value_of("INPUT").unwrap());
-    let counters = count_chars(&messages);
-    println!("Part 1: {}", part1(&counters));
-    println!("Part 2: {}", part2(&counters));
-}
-
-fn read_input(filename: &str) -> Vec<String> {
-    let file = match File::open(filename) {
-        Ok(file) => file,
-        Err(err) => {
-            println!("Failed to open file '{}': {}", filename, err.to_string());
-            exit(2);
-        }
-    };
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+    let (contents, filename) = read_input(args.value_of("INPUT"), Day06::DAY)
+        .unwrap_or_else(exit_with_error(Day06::DAY, format, 2));
+    let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+        .unwrap_or_else(exit_with_error(Day06::DAY, format, 2));
 
-    match BufReader::new(file).lines().collect::<Result<Vec<_>, _>>() {
-        Ok(messages) => messages,
-        Err(err) => {
-            println!(
-                "Failed to parse input file '{}': {}",
-                filename,
-                err.to_string()
-            );
-            exit(3);
-        }
+    let reports =
+        answers.check(run::<Day06>(&contents, &[Part::One, Part::Two]));
+    for report in &reports {
+        report.print(format, stats);
     }
+    exit(exit_code(&reports));
 }
@@ -1,5 +1,5 @@
+use aoc::{parse_lines, Error, Solution};
 use std::collections::HashSet;
-use std::io::{Error, ErrorKind};
 use std::str::FromStr;
 
 pub struct IP7 {
@@ -72,6 +72,28 @@ pub fn part2(ips: &[IP7]) -> usize {
     ips.iter().filter(|ip| ip.supports_ssl()).count()
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<IP7>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const DAY: u32 = 7;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input)
+    }
+
+    fn part1(ips: &Self::Input) -> Result<Option<usize>, Error> {
+        Ok(Some(part1(ips)))
+    }
+
+    fn part2(ips: &Self::Input) -> Result<Option<usize>, Error> {
+        Ok(Some(part2(ips)))
+    }
+}
+
 impl FromStr for IP7 {
     type Err = Error;
 
@@ -79,7 +101,7 @@ impl FromStr for IP7 {
         let mut components = Vec::new();
         let mut seq = String::new();
         let mut is_hypernet = false;
-        for ch in s.chars() {
+        for (index, ch) in s.chars().enumerate() {
             if (ch == '[' && !is_hypernet) || (ch == ']' && is_hypernet) {
                 components.push(IP7Component {
                     sequence: seq.clone(),
@@ -90,7 +112,7 @@ impl FromStr for IP7 {
             } else if ch != '[' && ch != ']' {
                 seq.push(ch);
             } else {
-                return Err(Error::new(ErrorKind::InvalidData, "Invalid IP7"));
+                return Err(Error::format(index + 1, ch, "Unbalanced bracket"));
             }
         }
         if !seq.is_empty() {
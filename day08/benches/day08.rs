@@ -0,0 +1,22 @@
+use aoc::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day08::Day08;
+
+const INPUT: &str = include_str!("../input.txt");
+
+fn parts(c: &mut Criterion) {
+    let input = Day08::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day08");
+    group
+        .bench_function("parse", |b| b.iter(|| Day08::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day08::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day08::part2(black_box(&input)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parts);
+criterion_main!(benches);
@@ -1,10 +1,35 @@
+use aoc::{column_at, parse_lines, Error, Solution};
 use regex::Regex;
+use serde::{Serialize, Serializer};
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
-use std::io::{Error, ErrorKind};
 use std::str::FromStr;
 use Instruction::*;
 
+pub const SCREEN_COLS: usize = 50;
+pub const SCREEN_ROWS: usize = 6;
+
+const LETTER_COLS: usize = 4;
+const LETTER_ROWS: usize = 6;
+const LETTERS: &[(char, &str)] = &[
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
 pub struct Screen {
     num_cols: usize,
     num_rows: usize,
@@ -26,17 +51,66 @@ impl Screen {
         }
     }
 
+    pub fn from_instructions(
+        num_cols: usize,
+        num_rows: usize,
+        instructions: &[Instruction],
+    ) -> Self {
+        let mut screen = Self::new(num_cols, num_rows);
+        screen.execute(instructions);
+        screen
+    }
+
     pub fn count_lit_pixels(&self) -> usize {
         self.lit_pixels.len()
     }
 
+    /// Returns the rows of the screen, with '#' for lit pixels and '.' for
+    /// pixels that are off.
+    pub fn pixels(&self) -> Vec<String> {
+        (0..self.num_rows)
+            .map(|row| {
+                (0..self.num_cols)
+                    .map(|col| {
+                        if self.lit_pixels.contains(&(col, row)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Reads the letters displayed on the screen, each drawn in a block of
+    /// 4x6 pixels followed by an empty column. Returns `None` if any block
+    /// is not a known letter.
+    pub fn decode(&self) -> Option<String> {
+        if self.num_rows != LETTER_ROWS {
+            return None;
+        }
+        let pixels = self.pixels();
+        (0..self.num_cols / (LETTER_COLS + 1))
+            .map(|index| {
+                let start = index * (LETTER_COLS + 1);
+                let block = pixels
+                    .iter()
+                    .map(|row| &row[start..start + LETTER_COLS])
+                    .collect::<String>();
+                LETTERS
+                    .iter()
+                    .find(|(_, letter)| *letter == block)
+                    .map(|&(ch, _)| ch)
+            })
+            .collect()
+    }
+
     pub fn execute(&mut self, instructions: &[Instruction]) {
-        instructions.iter().for_each(|instr| {
-            match *instr {
-                Rectangle(cols, rows) => self.draw_rectanle(cols, rows),
-                RotateRow(row, rotation) => self.rotate_row(row, rotation),
-                RotateColumn(col, rotation) => self.rotate_col(col, rotation),
-            }
+        instructions.iter().for_each(|instr| match *instr {
+            Rectangle(cols, rows) => self.draw_rectanle(cols, rows),
+            RotateRow(row, rotation) => self.rotate_row(row, rotation),
+            RotateColumn(col, rotation) => self.rotate_col(col, rotation),
         });
     }
 
@@ -103,6 +177,56 @@ impl Display for Screen {
     }
 }
 
+#[derive(Serialize)]
+struct ScreenOutput {
+    pixels: Vec<String>,
+    text: Option<String>,
+}
+
+impl Serialize for Screen {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        ScreenOutput {
+            pixels: self.pixels(),
+            text: self.decode(),
+        }
+        .serialize(serializer)
+    }
+}
+
+pub fn part1(instructions: &[Instruction]) -> usize {
+    Screen::from_instructions(SCREEN_COLS, SCREEN_ROWS, instructions)
+        .count_lit_pixels()
+}
+
+pub fn part2(instructions: &[Instruction]) -> Screen {
+    Screen::from_instructions(SCREEN_COLS, SCREEN_ROWS, instructions)
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Vec<Instruction>;
+    type Answer1 = usize;
+    type Answer2 = Screen;
+
+    const DAY: u32 = 8;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input)
+    }
+
+    fn part1(instructions: &Self::Input) -> Result<Option<usize>, Error> {
+        Ok(Some(part1(instructions)))
+    }
+
+    fn part2(instructions: &Self::Input) -> Result<Option<Screen>, Error> {
+        Ok(Some(part2(instructions)))
+    }
+}
+
 impl FromStr for Instruction {
     type Err = Error;
 
@@ -113,45 +237,67 @@ impl FromStr for Instruction {
             r"|(rotate column x=(?P<col>\d+) by (?P<col_rotation>\d+)))$",
         ))
         .unwrap();
-        let captures = pattern.captures(s).ok_or_else(|| {
-            Error::new(ErrorKind::InvalidData, "Invalid instruction")
-        })?;
-
-        if let Some((cols, rows)) = captures
-            .name("rect_cols")
-            .and_then(|s| s.as_str().parse().ok())
-            .and_then(|cols| {
-                captures
-                    .name("rect_rows")
-                    .and_then(|s| s.as_str().parse().ok())
-                    .map(|rows| (cols, rows))
+        let captures = pattern
+            .captures(s)
+            .ok_or_else(|| Error::format(1, s, "Invalid instruction"))?;
+        let number = |name: &str| -> Result<usize, Error> {
+            let value = captures.name(name).unwrap();
+            value.as_str().parse().map_err(|err| {
+                Error::value(column_at(s, value.start()), value.as_str(), err)
             })
-        {
-            Ok(Rectangle(cols, rows))
-        } else if let Some((row, rotation)) = captures
-            .name("row")
-            .and_then(|s| s.as_str().parse().ok())
-            .and_then(|row| {
-                captures
-                    .name("row_rotation")
-                    .and_then(|s| s.as_str().parse().ok())
-                    .map(|rotation| (row, rotation))
-            })
-        {
-            Ok(RotateRow(row, rotation))
-        } else if let Some((col, rotation)) = captures
-            .name("col")
-            .and_then(|s| s.as_str().parse().ok())
-            .and_then(|col| {
-                captures
-                    .name("col_rotation")
-                    .and_then(|s| s.as_str().parse().ok())
-                    .map(|rotation| (col, rotation))
-            })
-        {
-            Ok(RotateColumn(col, rotation))
+        };
+
+        if captures.name("rect_cols").is_some() {
+            Ok(Rectangle(number("rect_cols")?, number("rect_rows")?))
+        } else if captures.name("row").is_some() {
+            Ok(RotateRow(number("row")?, number("row_rotation")?))
         } else {
-            Err(Error::new(ErrorKind::InvalidData, "Invalid instruction"))
+            Ok(RotateColumn(number("col")?, number("col_rotation")?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        let instructions = parse_lines(
+            "rect 3x2\n\
+            rotate column x=1 by 1\n\
+            rotate row y=0 by 4\n\
+            rotate column x=1 by 1",
+        )
+        .unwrap();
+        let screen = Screen::from_instructions(7, 3, &instructions);
+        assert_eq!(screen.count_lit_pixels(), 6);
+        assert_eq!(screen.pixels(), vec![".#..#.#", "#.#....", ".#....."]);
+        assert_eq!(screen.decode(), None);
+    }
+
+    #[test]
+    fn decode_letters() {
+        let rows = [
+            "#..#.###.",
+            "#..#.#..#",
+            "#..#.#..#",
+            "#..#.###.",
+            "#..#.#...",
+            ".##..#...",
+        ];
+        let mut screen = Screen::new(10, 6);
+        for (row, pixels) in rows.iter().enumerate() {
+            for (col, pixel) in pixels.chars().enumerate() {
+                if pixel == '#' {
+                    screen.lit_pixels.insert((col, row));
+                }
+            }
         }
+        assert_eq!(screen.decode(), Some("UP".to_string()));
+        assert_eq!(
+            serde_json::to_value(&screen).unwrap()["text"],
+            serde_json::json!("UP")
+        );
     }
 }
@@ -1,8 +1,18 @@
+use aoc::answers::Answers;
+use aoc::input::read_input;
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, stats_arg, Format,
+    Part, Report,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::Solution;
 use clap::{crate_description, value_t_or_exit, App, Arg};
-use day08::{Instruction, Screen};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use day08::{Day08, Screen};
 use std::process::exit;
+use std::time::Instant;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
 
 fn main() {
     let args = App::new(crate_description!())
@@ -20,45 +30,61 @@ fn main() {
         )
         .arg(
             Arg::with_name("INPUT")
-                .help("Sets the input file to use")
-                .required(true)
+                .help("Sets the input file to use [default: cached input]")
                 .index(3),
         )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
         .get_matches();
 
-    println!(crate_description!());
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
     let num_cols = value_t_or_exit!(args.value_of("NUM_COLS"), usize);
     let num_rows = value_t_or_exit!(args.value_of("NUM_ROWS"), usize);
-    let instructions = read_input(args.value_of("INPUT").unwrap());
+    let (contents, filename) = read_input(args.value_of("INPUT"), Day08::DAY)
+        .unwrap_or_else(exit_with_error(Day08::DAY, format, 2));
 
-    let mut screen = Screen::new(num_cols, num_rows);
-    screen.execute(&instructions);
-    println!("Part 1: {}", screen.count_lit_pixels());
-    println!("Part 2:\n{}", screen);
-}
+    let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+        .unwrap_or_else(exit_with_error(Day08::DAY, format, 2));
 
-fn read_input(filename: &str) -> Vec<Instruction> {
-    let file = match File::open(filename) {
-        Ok(file) => file,
-        Err(err) => {
-            println!("Failed to open file '{}': {}", filename, err.to_string());
-            exit(2);
-        }
+    let start = Instant::now();
+    let parsed = Day08::parse(&contents);
+    let parse_time = start.elapsed();
+    let reports = match parsed {
+        Ok(instructions) => vec![
+            Report::time(Day08::DAY, Part::One, || {
+                let screen = Screen::from_instructions(
+                    num_cols,
+                    num_rows,
+                    &instructions,
+                );
+                Ok(Some(screen.count_lit_pixels()))
+            }),
+            Report::time(Day08::DAY, Part::Two, || {
+                Ok(Some(Screen::from_instructions(
+                    num_cols,
+                    num_rows,
+                    &instructions,
+                )))
+            }),
+        ],
+        Err(err) => vec![
+            Report::failed(Day08::DAY, Part::One, err.clone()),
+            Report::failed(Day08::DAY, Part::Two, err),
+        ],
     };
-
-    match BufReader::new(file)
-        .lines()
-        .map(|line| line.and_then(|value| value.parse()))
-        .collect()
-    {
-        Ok(ips) => ips,
-        Err(err) => {
-            println!(
-                "Failed to parse input file '{}': {}",
-                filename,
-                err.to_string()
-            );
-            exit(3);
-        }
+    let reports = answers.check(
+        reports
+            .into_iter()
+            .map(|report| report.parsed_in(parse_time))
+            .collect(),
+    );
+    for report in &reports {
+        report.print(format, stats);
     }
+    exit(exit_code(&reports));
 }
@@ -0,0 +1,48 @@
+use aoc::{Position, Solution};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day09::{decomp_len_iter, Day09};
+
+const INPUT: &str = include_str!("../input.txt");
+
+fn parts(c: &mut Criterion) {
+    let input = Day09::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day09");
+    group
+        .bench_function("parse", |b| b.iter(|| Day09::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day09::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day09::part2(black_box(&input)))
+    });
+    group.finish();
+}
+
+fn decompress(c: &mut Criterion) {
+    let chars = INPUT
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(index, ch)| {
+            (
+                Position {
+                    line: 1,
+                    column: index + 1,
+                },
+                ch,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut group = c.benchmark_group("day09 decomp_len_iter");
+    for &recursive in &[false, true] {
+        group.bench_function(format!("recursive={}", recursive), |b| {
+            b.iter(|| {
+                decomp_len_iter(chars.iter().copied().peekable(), recursive)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parts, decompress);
+criterion_main!(benches);
@@ -1,3 +1,4 @@
+use aoc::{Error, Position, Solution};
 use std::iter::Peekable;
 use Input::*;
 
@@ -7,56 +8,67 @@ enum Input {
     Compressed,
 }
 
-fn decomp_len(contents: &str, recursive: bool) -> Option<usize> {
+fn decomp_len(contents: &str, recursive: bool) -> Result<usize, Error> {
     decomp_len_iter(
         contents
-            .chars()
-            .filter(|&ch| !ch.is_whitespace())
+            .lines()
+            .enumerate()
+            .flat_map(|(line_index, line)| {
+                line.chars().enumerate().map(move |(index, ch)| {
+                    let line = line_index + 1;
+                    (
+                        Position {
+                            line,
+                            column: index + 1,
+                        },
+                        ch,
+                    )
+                })
+            })
+            .filter(|&(_, ch)| !ch.is_whitespace())
             .peekable(),
         recursive,
     )
 }
 
-fn decomp_len_iter<I>(mut chars: Peekable<I>, recursive: bool) -> Option<usize>
+/// Returns the decompressed length of the non-whitespace characters of a
+/// file, each paired with its position in the file.
+pub fn decomp_len_iter<I>(
+    mut chars: Peekable<I>,
+    recursive: bool,
+) -> Result<usize, Error>
 where
-    I: Iterator<Item = char>,
+    I: Iterator<Item = (Position, char)>,
 {
     let mut len = 0;
     let mut rep_len = 0;
     let mut rep_times = 0;
     let mut state = Uncompressed;
-    while chars.peek().is_some() {
+    while let Some(&(position, _)) = chars.peek() {
         match state {
             Uncompressed => {
-                len += (&mut chars).take_while(|&ch| ch != '(').count();
+                len += (&mut chars).take_while(|&(_, ch)| ch != '(').count();
                 state = RepeatSpec;
             }
             RepeatSpec => {
-                rep_len = (&mut chars)
-                    .take_while(|&ch| ch != 'x')
-                    .collect::<String>()
-                    .parse()
-                    .ok()?;
-                rep_times = (&mut chars)
-                    .take_while(|&ch| ch != ')')
-                    .collect::<String>()
-                    .parse()
-                    .ok()?;
+                rep_len = repeat_spec_number(&mut chars, 'x', position)?;
+                let position = chars.peek().map_or(position, |&(pos, _)| pos);
+                rep_times = repeat_spec_number(&mut chars, ')', position)?;
                 state = Compressed;
             }
             Compressed => {
+                let data = (&mut chars).take(rep_len).collect::<Vec<_>>();
+                if data.len() < rep_len {
+                    return Err(Error::format(
+                        position.column,
+                        data.iter().map(|&(_, ch)| ch).collect::<String>(),
+                        "Missing compressed data",
+                    )
+                    .at_line(position.line));
+                }
                 let data_len = if recursive {
-                    let data = (&mut chars).take(rep_len).collect::<Vec<_>>();
-                    if data.len() < rep_len {
-                        // Missing compressed data
-                        return None;
-                    }
                     decomp_len_iter(data.into_iter().peekable(), recursive)?
                 } else {
-                    if (&mut chars).take(rep_len).count() < rep_len {
-                        // Missing compressed data
-                        return None;
-                    }
                     rep_len
                 };
                 len += rep_times * data_len;
@@ -64,40 +76,94 @@ where
             }
         }
     }
-    Some(len)
+    Ok(len)
 }
 
-pub fn part1(contents: &str) -> Option<usize> {
+fn repeat_spec_number<I>(
+    chars: &mut Peekable<I>,
+    delimiter: char,
+    position: Position,
+) -> Result<usize, Error>
+where
+    I: Iterator<Item = (Position, char)>,
+{
+    let number = chars
+        .take_while(|&(_, ch)| ch != delimiter)
+        .map(|(_, ch)| ch)
+        .collect::<String>();
+    number.parse().map_err(|err| {
+        Error::format(
+            position.column,
+            number,
+            format!("Invalid repeat spec: {}", err),
+        )
+        .at_line(position.line)
+    })
+}
+
+pub fn part1(contents: &str) -> Result<usize, Error> {
     decomp_len(contents, false)
 }
 
-pub fn part2(contents: &str) -> Option<usize> {
+pub fn part2(contents: &str) -> Result<usize, Error> {
     decomp_len(contents, true)
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const DAY: u32 = 9;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(input.to_string())
+    }
+
+    fn part1(contents: &Self::Input) -> Result<Option<usize>, Error> {
+        part1(contents).map(Some)
+    }
+
+    fn part2(contents: &Self::Input) -> Result<Option<usize>, Error> {
+        part2(contents).map(Some)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn part1_examples() {
-        assert_eq!(part1("ADVENT"), Some(6));
-        assert_eq!(part1("A(1x5)BC"), Some(7));
-        assert_eq!(part1("(3x3)XYZ"), Some(9));
-        assert_eq!(part1("A(2x2)BCD(2x2)EFG"), Some(11));
-        assert_eq!(part1("(6x1)(1x3)A"), Some(6));
-        assert_eq!(part1("X(8x2)(3x3)ABCY"), Some(18));
+        assert_eq!(part1("ADVENT"), Ok(6));
+        assert_eq!(part1("A(1x5)BC"), Ok(7));
+        assert_eq!(part1("(3x3)XYZ"), Ok(9));
+        assert_eq!(part1("A(2x2)BCD(2x2)EFG"), Ok(11));
+        assert_eq!(part1("(6x1)(1x3)A"), Ok(6));
+        assert_eq!(part1("X(8x2)(3x3)ABCY"), Ok(18));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(part2("ADVENT"), Some(6));
-        assert_eq!(part2("(3x3)XYZ"), Some(9));
-        assert_eq!(part2("X(8x2)(3x3)ABCY"), Some(20));
-        assert_eq!(part2("(27x12)(20x12)(13x14)(7x10)(1x12)A"), Some(241920));
+        assert_eq!(part2("ADVENT"), Ok(6));
+        assert_eq!(part2("(3x3)XYZ"), Ok(9));
+        assert_eq!(part2("X(8x2)(3x3)ABCY"), Ok(20));
+        assert_eq!(part2("(27x12)(20x12)(13x14)(7x10)(1x12)A"), Ok(241920));
         assert_eq!(
             part2("(25x3)(3x3)ABC(2x3)XY(5x2)PQRSTX(18x9)(3x2)TWO(5x7)SEVEN"),
-            Some(445)
+            Ok(445)
         );
     }
+
+    #[test]
+    fn invalid_input() {
+        let err = part1("AB(3xC)DEF").err().unwrap();
+        assert_eq!(err.position(), Position { line: 1, column: 6 });
+        assert_eq!(err.text(), "C");
+        let err = part2("A\n(8x2)(3x3)AB").err().unwrap();
+        assert_eq!(err.position(), Position { line: 2, column: 6 });
+        assert_eq!(err.reason(), "Missing compressed data");
+    }
 }
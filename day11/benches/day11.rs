@@ -0,0 +1,35 @@
+use aoc::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day11::{Building, Day11};
+
+const INPUT: &str = include_str!("../input");
+
+fn parts(c: &mut Criterion) {
+    let input = Day11::parse(INPUT).unwrap();
+    let mut group = c.benchmark_group("day11");
+    // Each iteration takes seconds, so keep the number of samples low
+    group.sample_size(10);
+    group
+        .bench_function("parse", |b| b.iter(|| Day11::parse(black_box(INPUT))));
+    group.bench_function("part1", |b| {
+        b.iter(|| Day11::part1(black_box(&input)))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| Day11::part2(black_box(&input)))
+    });
+    group.finish();
+}
+
+fn building(c: &mut Criterion) {
+    let start: Building = Day11::parse(INPUT).unwrap();
+    let canonical = start.clone().canonical();
+    c.bench_function("day11 Building::canonical", |b| {
+        b.iter(|| black_box(&start).clone().canonical())
+    });
+    c.bench_function("day11 Building::possible_moves", |b| {
+        b.iter(|| black_box(&canonical).possible_moves().count())
+    });
+}
+
+criterion_group!(benches, parts, building);
+criterion_main!(benches);
@@ -1,3 +1,5 @@
+use aoc::stats::record;
+use aoc::{column_at, Error, Solution};
 use regex::Regex;
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
@@ -40,7 +42,9 @@ pub struct Building {
 }
 
 impl Building {
-    fn canonical(mut self) -> Self {
+    /// Renumbers elements in order of appearance so that buildings that only
+    /// differ by a permutation of elements are equal.
+    pub fn canonical(mut self) -> Self {
         let mut next_element = 0;
         let mut mapping: HashMap<Element, Element> = HashMap::new();
 
@@ -122,7 +126,9 @@ impl Building {
         }
     }
 
-    fn possible_moves(&self) -> impl Iterator<Item = Self> + '_ {
+    /// All safe buildings reachable by moving the elevator one floor with
+    /// one or two objects.
+    pub fn possible_moves(&self) -> impl Iterator<Item = Self> + '_ {
         let mut destination_floor = Vec::new();
         if self.elevator > 0 {
             destination_floor.push(self.elevator - 1);
@@ -263,27 +269,48 @@ impl State {
 fn find_min_moves(start: &Building) -> Option<usize> {
     let mut states: BinaryHeap<State> = BinaryHeap::new();
     let mut seen = HashSet::new();
+    let mut search = SearchStats::default();
 
     let start_ptr = Rc::new(start.clone().canonical());
     seen.insert(Rc::clone(&start_ptr));
     states.push(State::new(0, start_ptr));
 
     while let Some(state) = states.pop() {
+        search.expanded += 1;
         let num_moves = state.num_moves + 1;
         for next_building in state.building.possible_moves() {
             let building_ptr = Rc::new(next_building.canonical());
             if !seen.insert(Rc::clone(&building_ptr)) {
+                search.deduplicated += 1;
                 continue;
             }
             if building_ptr.all_at_top_floor() {
+                search.record();
                 return Some(num_moves);
             }
             states.push(State::new(num_moves, building_ptr));
+            search.max_heap_size = search.max_heap_size.max(states.len());
         }
     }
+    search.record();
     None
 }
 
+#[derive(Default)]
+struct SearchStats {
+    expanded: usize,
+    deduplicated: usize,
+    max_heap_size: usize,
+}
+
+impl SearchStats {
+    fn record(&self) {
+        record("states expanded", self.expanded);
+        record("states deduplicated", self.deduplicated);
+        record("max heap size", self.max_heap_size);
+    }
+}
+
 pub fn part1(start: &Building) -> Option<usize> {
     find_min_moves(start)
 }
@@ -292,8 +319,30 @@ pub fn part2(start: &Building) -> Option<usize> {
     find_min_moves(&start.add_new_element_pair(0)?.add_new_element_pair(0)?)
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Building;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const DAY: u32 = 11;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input.parse()
+    }
+
+    fn part1(building: &Self::Input) -> Result<Option<usize>, Error> {
+        Ok(part1(building))
+    }
+
+    fn part2(building: &Self::Input) -> Result<Option<usize>, Error> {
+        Ok(part2(building))
+    }
+}
+
 impl FromStr for Building {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let floor_regex = Regex::new(concat!(
@@ -311,16 +360,23 @@ impl FromStr for Building {
         let mut floors = vec![vec![]; NUM_FLOORS];
         let mut element_map = HashMap::new();
         let mut next_element_id = 0;
-        for line in s.lines() {
-            let floor_cap = floor_regex
-                .captures(line)
-                .ok_or_else(|| format!("Invalid input: {}", line))?;
-            let floor_index = match floor_cap.name("floor").unwrap().as_str() {
+        for (line_index, line) in s.lines().enumerate() {
+            let floor_cap = floor_regex.captures(line).ok_or_else(|| {
+                Error::format(1, line, "Invalid floor description")
+                    .at_line(line_index + 1)
+            })?;
+            let floor = floor_cap.name("floor").unwrap();
+            let floor_index = match floor.as_str() {
                 "first" => Ok(0),
                 "second" => Ok(1),
                 "third" => Ok(2),
                 "fourth" => Ok(3),
-                f => Err(format!("Invalid floor number: {}", f)),
+                f => Err(Error::value(
+                    column_at(line, floor.start()),
+                    f,
+                    "Invalid floor number",
+                )
+                .at_line(line_index + 1)),
             }?;
             let mut floor_objects = Vec::new();
             if let Some(objects) = floor_cap.name("objects") {
@@ -382,4 +438,13 @@ mod tests {
         let building = input.parse().unwrap();
         assert_eq!(find_min_moves(&building), Some(11));
     }
+
+    #[test]
+    fn invalid_floor() {
+        let input = "The first floor contains nothing relevant.\n\
+            The fifth floor contains a hydrogen generator.";
+        let err = input.parse::<Building>().err().unwrap();
+        assert_eq!(err.position(), aoc::Position { line: 2, column: 5 });
+        assert_eq!(err.text(), "fifth");
+    }
 }
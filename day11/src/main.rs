@@ -1,40 +1,44 @@
-use clap::{crate_description, App, Arg};
-use day11::*;
-use std::fs::read_to_string;
+use aoc::answers::Answers;
+use aoc::input::read_input;
+use aoc::report::{
+    answers_arg, exit_code, exit_with_error, format_arg, run, stats_arg,
+    Format, Part,
+};
+use aoc::stats::TrackingAllocator;
+use aoc::Solution;
+use clap::{crate_description, value_t_or_exit, App, Arg};
+use day11::Day11;
 use std::process::exit;
 
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
 fn main() {
     let args = App::new(crate_description!())
         .arg(
             Arg::with_name("INPUT")
-                .help("File with puzzle input")
-                .required(true)
+                .help("File with puzzle input [default: cached input]")
                 .index(1),
         )
+        .arg(format_arg())
+        .arg(answers_arg())
+        .arg(stats_arg())
         .get_matches();
 
-    println!(crate_description!());
-
-    let input = match read_input(args.value_of("INPUT").unwrap()) {
-        Ok(data) => data,
-        Err(err) => {
-            println!("Failed to read input: {}", err);
-            exit(2);
-        }
-    };
-
-    match part1(&input) {
-        Some(result) => println!("Part 1: {}", result),
-        None => println!("Part 1: not found"),
-    };
-    match part2(&input) {
-        Some(result) => println!("Part 2: {}", result),
-        None => println!("Part 2: not found"),
-    };
-}
+    let format = value_t_or_exit!(args.value_of("FORMAT"), Format);
+    let stats = args.is_present("STATS");
+    if format == Format::Text {
+        println!(crate_description!());
+    }
+    let (contents, filename) = read_input(args.value_of("INPUT"), Day11::DAY)
+        .unwrap_or_else(exit_with_error(Day11::DAY, format, 2));
+    let answers = Answers::find(args.value_of("ANSWERS"), &filename)
+        .unwrap_or_else(exit_with_error(Day11::DAY, format, 2));
 
-fn read_input(filename: &str) -> Result<Building, String> {
-    read_to_string(filename)
-        .map_err(|err| err.to_string())
-        .and_then(|contents| contents.parse())
+    let reports =
+        answers.check(run::<Day11>(&contents, &[Part::One, Part::Two]));
+    for report in &reports {
+        report.print(format, stats);
+    }
+    exit(exit_code(&reports));
 }